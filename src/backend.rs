@@ -0,0 +1,473 @@
+//! The module that contains the rendering backends that a window can draw to.
+//! [`CrosstermBackend`] writes terminal commands to any [`Write`] target, and
+//! [`MemoryBackend`] keeps the screen in memory, which is useful for testing.
+//! 
+//! It also contains [`set_panic_hook()`], which makes sure a panic doesn't leave
+//! the terminal in raw mode.
+
+use crate::buffer::{Buffer, Cell};
+use crate::text;
+use crate::traits::Backend;
+
+use crossterm::{cursor, execute, queue};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::style::{
+    Attribute,
+    Attributes,
+    Color,
+    Print,
+    ResetColor,
+    SetAttribute,
+    SetAttributes,
+    SetBackgroundColor,
+    SetForegroundColor,
+};
+use crossterm::terminal::*;
+
+use std::cell::RefCell;
+use std::io::{self, stdout, Stdout, Write};
+use std::ops::{Deref, DerefMut};
+use std::panic;
+use std::rc::Rc;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(test)]
+/// The module containing tests for these structs
+mod test {
+
+    use super::*;
+
+    use std::cell::Cell;
+
+    /* Tests for the `BackendGuard` struct */
+
+    /// A backend that only counts how often it was entered and left
+    struct CountingBackend {
+        entered: Rc<Cell<u32>>,
+        left: Rc<Cell<u32>>,
+    }
+    impl Backend for CountingBackend {
+        fn clear(&mut self) -> io::Result<()> { Ok(()) }
+        fn enter(&mut self) -> io::Result<()> {
+            self.entered.set(self.entered.get() + 1);
+            Ok(())
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+        fn leave(&mut self) -> io::Result<()> {
+            self.left.set(self.left.get() + 1);
+            Ok(())
+        }
+        fn move_to(&mut self, _x: u16, _y: u16) -> io::Result<()> { Ok(()) }
+        fn print(&mut self, _text: &str) -> io::Result<()> { Ok(()) }
+        fn reset_style(&mut self) -> io::Result<()> { Ok(()) }
+        fn set_attributes(&mut self, _attributes: Attributes) -> io::Result<()> { Ok(()) }
+        fn set_bg(&mut self, _color: Color) -> io::Result<()> { Ok(()) }
+        fn set_fg(&mut self, _color: Color) -> io::Result<()> { Ok(()) }
+        fn size(&self) -> io::Result<(u16, u16)> { Ok((0, 0)) }
+    }
+
+    #[test]
+    /// Test that a [`BackendGuard`] enters its backend once, and leaves it exactly
+    /// once: either when asked to, or when it is dropped
+    fn test_backend_guard() {
+        let entered = Rc::new(Cell::new(0));
+        let left = Rc::new(Cell::new(0));
+        let counting = || CountingBackend { entered: entered.clone(), left: left.clone() };
+
+        // Never entered, so there is nothing to leave
+        drop(BackendGuard::new(Box::new(counting())));
+        assert_eq!(left.get(), 0);
+
+        // Left by hand, and not again when dropped
+        let mut guard = BackendGuard::new(Box::new(counting()));
+        guard.enter().unwrap();
+        guard.enter().unwrap();
+        guard.leave().unwrap();
+        guard.leave().unwrap();
+        drop(guard);
+        assert_eq!((entered.get(), left.get()), (1, 1));
+
+        // Left when dropped, e.g. while unwinding from a panic
+        let mut guard = BackendGuard::new(Box::new(counting()));
+        guard.enter().unwrap();
+        drop(guard);
+        assert_eq!((entered.get(), left.get()), (2, 2));
+    }
+
+    /* Tests for the `CrosstermBackend` struct */
+
+    #[test]
+    /// Test that [`CrosstermBackend`] writes to any [`Write`] target
+    fn test_crossterm_backend_writer() {
+        let mut backend = CrosstermBackend::new(Vec::new()).fixed_size(10, 2);
+        backend.move_to(1, 1).unwrap();
+        backend.print("hi").unwrap();
+        backend.flush().unwrap();
+
+        let output = String::from_utf8(backend.writer().clone()).unwrap();
+        assert!(output.ends_with("hi"));
+        assert_eq!(backend.size().unwrap(), (10, 2));
+    }
+
+    /* Tests for the `MemoryBackend` struct */
+
+    #[test]
+    /// Test that [`MemoryBackend`] prints into its buffer
+    fn test_memory_backend_print() {
+        let mut backend = MemoryBackend::new(10, 2);
+        backend.move_to(2, 1).unwrap();
+        backend.set_fg(Color::Red).unwrap();
+        backend.print("abc").unwrap();
+
+        let buffer = backend.buffer();
+        assert_eq!(buffer.cell(2, 1).unwrap().symbol, "a");
+        assert_eq!(buffer.cell(4, 1).unwrap().symbol, "c");
+        assert_eq!(buffer.cell(4, 1).unwrap().fg, Color::Red);
+        assert_eq!(buffer.cell(5, 1).unwrap().symbol, " ");
+    }
+
+    #[test]
+    /// Test that clones of a [`MemoryBackend`] share the same screen
+    fn test_memory_backend_shared() {
+        let backend = MemoryBackend::new(4, 1);
+        let mut clone = backend.clone();
+        clone.print("ab").unwrap();
+        assert_eq!(backend.buffer().cell(1, 0).unwrap().symbol, "b");
+    }
+}
+
+/// The [`Backend`] of a window. It makes sure the backend is left exactly once
+/// after it was entered: when the window quits, or otherwise when the window is
+/// dropped, even while unwinding from a panic.
+pub(crate) struct BackendGuard {
+    /// The backend itself
+    backend: Box<dyn Backend>,
+    /// Whether the backend was entered, and hasn't been left since
+    entered: bool,
+}
+impl BackendGuard {
+
+    /// Create a new guard for `backend`, which hasn't been entered yet
+    pub(crate) fn new(backend: Box<dyn Backend>) -> BackendGuard {
+        BackendGuard { backend, entered: false }
+    }
+
+    /// Enter the backend with [`Backend::enter()`], unless it was already entered
+    pub(crate) fn enter(&mut self) -> io::Result<()> {
+        if !self.entered {
+            self.backend.enter()?;
+            self.entered = true;
+        }
+        Ok(())
+    }
+
+    /// Leave the backend with [`Backend::leave()`], unless it was already left
+    pub(crate) fn leave(&mut self) -> io::Result<()> {
+        if self.entered {
+            self.entered = false;
+            self.backend.leave()?;
+        }
+        Ok(())
+    }
+}
+impl Deref for BackendGuard {
+    type Target = dyn Backend;
+
+    fn deref(&self) -> &(dyn Backend + 'static) {
+        &*self.backend
+    }
+}
+impl DerefMut for BackendGuard {
+    fn deref_mut(&mut self) -> &mut (dyn Backend + 'static) {
+        &mut *self.backend
+    }
+}
+impl Drop for BackendGuard {
+    fn drop(&mut self) {
+
+        // There is nobody left to report an error to
+        let _ = self.leave();
+    }
+}
+
+/// A [`Backend`] that writes crossterm commands to any [`Write`] target: the
+/// terminal's stdout, a file, a socket, or a plain [`Vec<u8>`].
+///
+/// Example:
+///
+/// ```
+/// use rusty_panther::backend::CrosstermBackend;
+///
+/// fn main() {
+///
+///     // Render everything into a byte vector with a fixed size of 80×24
+///     let backend = CrosstermBackend::new(Vec::new()).fixed_size(80, 24);
+/// }
+/// ```
+pub struct CrosstermBackend<W: Write> {
+    /// Whether to enable raw mode on [`Backend::enter()`]; only makes sense when
+    /// the writer is the process' own terminal
+    raw_mode: bool,
+    /// The fixed size of the screen, if there is one; otherwise the terminal's
+    /// size is used
+    size_: Option<(u16, u16)>,
+    /// The target that all the commands are written to
+    writer: W,
+}
+impl<W: Write> CrosstermBackend<W> {
+
+    /// Create a new backend writing to `writer`. The screen size is the size of
+    /// the terminal, unless set with [`CrosstermBackend::fixed_size()`].
+    pub fn new(writer: W) -> CrosstermBackend<W> {
+        CrosstermBackend { raw_mode: false, size_: None, writer }
+    }
+
+    /// Return a reference to the writer that this backend writes to
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Return a mutable reference to the writer that this backend writes to
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+
+    /// Set whether to enable raw mode when the window starts using this backend.
+    /// Only turn this on when `writer` is the process' own terminal.
+    pub fn raw_mode(mut self, raw_mode: bool) -> CrosstermBackend<W> {
+        self.raw_mode = raw_mode;
+        self
+    }
+
+    /// Give the screen a fixed size of `width`×`height`, instead of using the
+    /// terminal's size.
+    pub fn fixed_size(mut self, width: u16, height: u16) -> CrosstermBackend<W> {
+        self.size_ = Some((width, height));
+        self
+    }
+}
+impl CrosstermBackend<Stdout> {
+
+    /// Create a new backend writing to the terminal's stdout, in raw mode. This
+    /// is the backend that windows use by default.
+    pub fn stdout() -> CrosstermBackend<Stdout> {
+        CrosstermBackend::new(stdout()).raw_mode(true)
+    }
+}
+impl<W: Write> Backend for CrosstermBackend<W> {
+
+    fn clear(&mut self) -> io::Result<()> {
+        queue!(self.writer, Clear(ClearType::All))
+    }
+
+    fn enter(&mut self) -> io::Result<()> {
+        if self.raw_mode {
+            enable_raw_mode()?;
+            RAW_MODE.store(true, Ordering::SeqCst);
+        }
+        execute!(self.writer, EnterAlternateScreen, EnableMouseCapture)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn leave(&mut self) -> io::Result<()> {
+        execute!(self.writer, DisableMouseCapture, LeaveAlternateScreen)?;
+        if self.raw_mode {
+            RAW_MODE.store(false, Ordering::SeqCst);
+            disable_raw_mode()?;
+        }
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        queue!(self.writer, cursor::MoveTo(x, y))
+    }
+
+    fn print(&mut self, text: &str) -> io::Result<()> {
+        queue!(self.writer, Print(text))
+    }
+
+    fn reset_style(&mut self) -> io::Result<()> {
+        queue!(self.writer, SetAttribute(Attribute::Reset), ResetColor)
+    }
+
+    fn set_attributes(&mut self, attributes: Attributes) -> io::Result<()> {
+        queue!(self.writer, SetAttribute(Attribute::Reset), SetAttributes(attributes))
+    }
+
+    fn set_bg(&mut self, color: Color) -> io::Result<()> {
+        queue!(self.writer, SetBackgroundColor(color))
+    }
+
+    fn set_fg(&mut self, color: Color) -> io::Result<()> {
+        queue!(self.writer, SetForegroundColor(color))
+    }
+
+    fn size(&self) -> io::Result<(u16, u16)> {
+        match self.size_ {
+            Some(size) => Ok(size),
+            None => size(),
+        }
+    }
+}
+
+/// A [`Backend`] that keeps the screen in memory as a [`Buffer`], instead of
+/// writing it anywhere. Clones of a memory backend share the same screen, so a
+/// clone can be handed to a window while the original is used to look at what
+/// was drawn.
+///
+/// Example:
+///
+/// ```
+/// use rusty_panther::backend::MemoryBackend;
+///
+/// fn main() {
+///     let backend = MemoryBackend::new(80, 24);
+///     assert_eq!(backend.buffer().get_width(), 80);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MemoryBackend {
+    /// The screen state, shared between all the clones of this backend
+    screen: Rc<RefCell<MemoryScreen>>,
+}
+impl MemoryBackend {
+
+    /// Create a new memory backend with a blank screen of size `width`×`height`
+    pub fn new(width: u16, height: u16) -> MemoryBackend {
+        MemoryBackend {
+            screen: Rc::new(RefCell::new(MemoryScreen {
+                buffer: Buffer::new(width, height),
+                cursor: (0, 0),
+                style: Cell::default(),
+            })),
+        }
+    }
+
+    /// Return a copy of everything that is currently on the screen
+    pub fn buffer(&self) -> Buffer {
+        self.screen.borrow().buffer.clone()
+    }
+}
+impl Backend for MemoryBackend {
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.screen.borrow_mut().buffer.reset();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.screen.borrow_mut().cursor = (x, y);
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str) -> io::Result<()> {
+        let mut screen = self.screen.borrow_mut();
+        for grapheme in text::graphemes(text) {
+            let (x, y) = screen.cursor;
+            let width = text::grapheme_width(grapheme) as u16;
+
+            // Like a terminal, a wide grapheme covers the cell after it too
+            for i in 0..width {
+                let mut cell = screen.style.clone();
+                cell.symbol = if i == 0 { grapheme.to_string() } else { String::new() };
+                if let Some(target) = screen.buffer.cell_mut(x.saturating_add(i), y) {
+                    *target = cell;
+                }
+            }
+            screen.cursor = (x.saturating_add(width), y);
+        }
+        Ok(())
+    }
+
+    fn reset_style(&mut self) -> io::Result<()> {
+        self.screen.borrow_mut().style = Cell::default();
+        Ok(())
+    }
+
+    fn set_attributes(&mut self, attributes: Attributes) -> io::Result<()> {
+        self.screen.borrow_mut().style.attributes = attributes;
+        Ok(())
+    }
+
+    fn set_bg(&mut self, color: Color) -> io::Result<()> {
+        self.screen.borrow_mut().style.bg = color;
+        Ok(())
+    }
+
+    fn set_fg(&mut self, color: Color) -> io::Result<()> {
+        self.screen.borrow_mut().style.fg = color;
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<(u16, u16)> {
+        let screen = self.screen.borrow();
+        Ok((screen.buffer.get_width(), screen.buffer.get_height()))
+    }
+}
+
+/// The screen state of a [`MemoryBackend`]
+struct MemoryScreen {
+    /// Everything that is currently on the screen
+    buffer: Buffer,
+    /// The position of the cursor
+    cursor: (u16, u16),
+    /// The colors and attributes used for printing; the symbol is ignored
+    style: Cell,
+}
+
+/// Whether a [`CrosstermBackend`] in raw mode was entered and hasn't been left
+/// yet, i.e. whether there is a terminal to restore
+static RAW_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Makes sure [`set_panic_hook()`] only installs its hook once
+static PANIC_HOOK: Once = Once::new();
+
+/// Restore the process' own terminal: leave the alternate screen, stop capturing
+/// the mouse, and disable raw mode. This is what a window using
+/// [`CrosstermBackend::stdout()`] does when it quits. Does nothing unless such
+/// a backend in raw mode is active, so other backends' output is left alone.
+pub fn restore_terminal() -> io::Result<()> {
+    if !RAW_MODE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
+    disable_raw_mode()
+}
+
+/// Install a panic hook that restores the terminal with [`restore_terminal()`]
+/// before the panic message is printed, and then runs the hook that was
+/// installed before. Without it, the message is printed into the alternate
+/// screen and lost, since windows only restore the terminal once the panic
+/// unwinds past them. Only the first call installs the hook; later ones do
+/// nothing.
+/// 
+/// This can also be done with [`Window::panic_hook()`](crate::widgets::Window::panic_hook()).
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::backend;
+/// 
+/// fn main() {
+///     backend::set_panic_hook();
+/// }
+/// ```
+pub fn set_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore_terminal();
+            hook(info);
+        }));
+    });
+}
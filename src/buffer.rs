@@ -0,0 +1,297 @@
+//! The module that contains the off-screen cell buffer. Widgets draw into a
+//! [`Buffer`] instead of writing straight to the terminal, and the window then
+//! writes only the cells that changed since the last frame.
+
+use crate::text;
+
+use crossterm::style::{Attribute, Attributes, Color};
+
+#[cfg(test)]
+/// The module containing tests for these structs
+mod test {
+
+    use super::*;
+
+    /* Tests for the `Buffer` struct */
+
+    #[test]
+    /// Test that [`Buffer::set_string()`] gives wide graphemes two cells, and
+    /// that [`Buffer::find()`] returns the column of the text
+    fn test_set_string_wide() {
+        let mut buffer = Buffer::new(6, 1);
+        buffer.set_string(0, 0, "日本x", Color::Reset, Color::Reset);
+        assert_eq!(buffer.cell(1, 0).unwrap().symbol, "");
+        assert_eq!(buffer.cell(2, 0).unwrap().symbol, "本");
+        assert_eq!(buffer.line(0), "日本x ");
+        assert_eq!(buffer.find("x"), Some((4, 0)));
+
+        // Writing over half of a wide grapheme blanks the other half, and a wide
+        // grapheme that doesn't fit at the edge isn't written at all
+        buffer.set_string(1, 0, "a", Color::Reset, Color::Reset);
+        assert_eq!(buffer.line(0), " a本x ");
+        buffer.set_string(5, 0, "語", Color::Reset, Color::Reset);
+        assert_eq!(buffer.cell(5, 0).unwrap().symbol, " ");
+    }
+
+    #[test]
+    /// Test the [`Buffer::diff()`] method
+    fn test_diff() {
+
+        // Create two identical buffers, then change one cell in the second
+        let previous = Buffer::new(10, 5);
+        let mut current = Buffer::new(10, 5);
+        current.set_string(2, 3, "a", Color::Red, Color::Reset);
+
+        let diff = previous.diff(&current);
+        assert_eq!(diff.len(), 1);
+        assert_eq!((diff[0].0, diff[0].1), (2, 3));
+        assert_eq!(diff[0].2.symbol, "a");
+        assert_eq!(diff[0].2.fg, Color::Red);
+    }
+
+    #[test]
+    /// Test that [`Buffer::diff()`] returns every cell when the sizes differ
+    fn test_diff_resized() {
+        let previous = Buffer::new(0, 0);
+        let current = Buffer::new(4, 3);
+        assert_eq!(previous.diff(&current).len(), 12);
+    }
+
+    #[test]
+    /// Test the [`Buffer::fill()`] method
+    fn test_fill() {
+        let mut buffer = Buffer::new(10, 5);
+        let cell = Cell { bg: Color::Blue, symbol: String::from("#"), ..Cell::default() };
+        buffer.fill(1, 1, 3, 2, &cell);
+
+        assert_eq!(buffer.cell(0, 0).unwrap().symbol, " ");
+        assert_eq!(buffer.cell(1, 1).unwrap().symbol, "#");
+        assert_eq!(buffer.cell(3, 2).unwrap().bg, Color::Blue);
+        assert_eq!(buffer.cell(4, 2).unwrap().bg, Color::Reset);
+    }
+
+    #[test]
+    /// Test the [`Buffer::find()`] and [`Buffer::line()`] methods
+    fn test_find() {
+        let mut buffer = Buffer::new(10, 3);
+        buffer.set_string(3, 2, "Label", Color::Reset, Color::Reset);
+
+        assert_eq!(buffer.line(2), "   Label  ");
+        assert_eq!(buffer.find("Label"), Some((3, 2)));
+        assert_eq!(buffer.find("Labels"), None);
+    }
+
+    #[test]
+    /// Test that [`Buffer::set_string()`] clips text at the edge of the buffer
+    fn test_set_string_clipped() {
+        let mut buffer = Buffer::new(4, 1);
+        buffer.set_string(2, 0, "abc", Color::Reset, Color::Reset);
+
+        assert_eq!(buffer.cell(2, 0).unwrap().symbol, "a");
+        assert_eq!(buffer.cell(3, 0).unwrap().symbol, "b");
+        assert!(buffer.cell(4, 0).is_none());
+    }
+}
+
+/// A single character cell of a [`Buffer`]: the symbol printed in it, its
+/// colors, and its text attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    /// The attributes (bold, underlined, etc.) of this cell
+    pub attributes: Attributes,
+    /// The background color of this cell
+    pub bg: Color,
+    /// The foreground color of this cell
+    pub fg: Color,
+    /// The grapheme printed in this cell; empty if the cell is covered by a wide
+    /// grapheme in the cell to its left
+    pub symbol: String,
+}
+impl Cell {
+
+    /// Reset this cell to a blank cell with the terminal's default colors
+    pub fn reset(&mut self) {
+        *self = Cell::default();
+    }
+}
+impl Default for Cell {
+    fn default() -> Cell {
+        Cell {
+            attributes: Attributes::default(),
+            bg: Color::Reset,
+            fg: Color::Reset,
+            symbol: String::from(" "),
+        }
+    }
+}
+
+/// An off-screen grid of [`Cell`]s that widgets draw into.
+///
+/// Everything drawn outside of the buffer's area is silently clipped, so
+/// widgets don't have to check the screen size themselves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Buffer {
+    /// All the cells in this buffer, row by row
+    cells: Vec<Cell>,
+    /// The height of the buffer, in chars
+    height: u16,
+    /// The width of the buffer, in chars
+    width: u16,
+}
+impl Buffer {
+
+    /// Create a new blank buffer of size `width`×`height`
+    pub fn new(width: u16, height: u16) -> Buffer {
+        Buffer {
+            cells: vec![Cell::default(); width as usize * height as usize],
+            height,
+            width,
+        }
+    }
+
+    /// Return the cell at (`x`, `y`), or [`None`] if it is outside the buffer
+    pub fn cell(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    /// Return the cell at (`x`, `y`) mutably, or [`None`] if it is outside the
+    /// buffer
+    pub fn cell_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        self.index_of(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    /// Compare this buffer (the previous frame) with `other` (the next frame),
+    /// returning the position and contents of every cell that changed. If the
+    /// buffers aren't the same size, every cell of `other` is returned.
+    pub fn diff<'b>(&self, other: &'b Buffer) -> Vec<(u16, u16, &'b Cell)> {
+        let resized = self.width != other.width || self.height != other.height;
+        let mut changed = Vec::new();
+        for (i, cell) in other.cells.iter().enumerate() {
+            if resized || self.cells[i] != *cell {
+                let x = (i % other.width as usize) as u16;
+                let y = (i / other.width as usize) as u16;
+                changed.push((x, y, cell));
+            }
+        }
+        changed
+    }
+
+    /// Fill the `width`×`height` area at (`x`, `y`) with copies of `cell`
+    pub fn fill(&mut self, x: u16, y: u16, width: u16, height: u16, cell: &Cell) {
+        for row in y..y.saturating_add(height) {
+            self.split_wide(x, row, width);
+            for column in x..x.saturating_add(width) {
+                if let Some(c) = self.cell_mut(column, row) {
+                    *c = cell.clone();
+                }
+            }
+        }
+    }
+
+    /// Find the first occurrence of `text` on a single line of the buffer,
+    /// returning the position of its first cell
+    pub fn find(&self, text: &str) -> Option<(u16, u16)> {
+        if text.is_empty() { return None; }
+        for y in 0..self.height {
+            let line = self.line(y);
+            if let Some(i) = line.find(text) {
+
+                // The cells covered by wide graphemes are empty, so the width of
+                // the text before the match is its column
+                return Some((text::width(&line[..i]) as u16, y));
+            }
+        }
+        None
+    }
+
+    /// Return the height of the buffer, in chars
+    pub fn get_height(&self) -> u16 { self.height }
+
+    /// Return the width of the buffer, in chars
+    pub fn get_width(&self) -> u16 { self.width }
+
+    /// Return the text of line `y` of the buffer, or an empty [`String`] if `y`
+    /// is outside the buffer
+    pub fn line(&self, y: u16) -> String {
+        (0..self.width)
+            .filter_map(|x| self.cell(x, y))
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    /// Reset every cell of the buffer to a blank cell
+    pub fn reset(&mut self) {
+        for cell in &mut self.cells {
+            cell.reset();
+        }
+    }
+
+    /// Resize the buffer to `width`×`height`, clearing all of its contents
+    pub fn resize(&mut self, width: u16, height: u16) {
+        *self = Buffer::new(width, height);
+    }
+
+    /// Add `attribute` to the `width` cells starting at (`x`, `y`)
+    pub fn set_attribute(&mut self, x: u16, y: u16, width: u16, attribute: Attribute) {
+        for column in x..x.saturating_add(width) {
+            if let Some(cell) = self.cell_mut(column, y) {
+                cell.attributes.set(attribute);
+            }
+        }
+    }
+
+    /// Write `text` starting at (`x`, `y`), one grapheme per cell, using the
+    /// colors `fg` and `bg`. Wide graphemes take up two cells, the second of which
+    /// is left empty. Text running past the right edge of the buffer is clipped.
+    pub fn set_string(&mut self, x: u16, y: u16, text: &str, fg: Color, bg: Color) {
+        let text = text::clip(text, self.width.saturating_sub(x) as usize);
+        self.split_wide(x, y, text::width(text) as u16);
+        let mut column = x;
+        for grapheme in text::graphemes(text) {
+            let width = text::grapheme_width(grapheme) as u16;
+            for i in 0..width {
+                if let Some(cell) = self.cell_mut(column + i, y) {
+                    cell.symbol = if i == 0 { grapheme.to_string() } else { String::new() };
+                    cell.fg = fg;
+                    cell.bg = bg;
+                }
+            }
+            column += width;
+        }
+    }
+
+    /// Blank out the halves of the wide graphemes that are cut in two by writing
+    /// over the `width` cells starting at (`x`, `y`)
+    fn split_wide(&mut self, x: u16, y: u16, width: u16) {
+        if width == 0 { return; }
+        let end = x.saturating_add(width);
+        if self.cell(x, y).is_some_and(|cell| cell.symbol.is_empty()) && x > 0 {
+            if let Some(cell) = self.cell_mut(x - 1, y) {
+                cell.symbol = String::from(" ");
+            }
+        }
+        if let Some(cell) = self.cell_mut(end, y) {
+            if cell.symbol.is_empty() {
+                cell.symbol = String::from(" ");
+            }
+        }
+    }
+
+    /// Return the text of the whole buffer, one line per row
+    pub fn text(&self) -> String {
+        (0..self.height)
+            .map(|y| self.line(y))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Return the index in `cells` of the cell at (`x`, `y`)
+    fn index_of(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        }
+        else {
+            None
+        }
+    }
+}
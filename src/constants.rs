@@ -10,4 +10,15 @@ impl chars {
     pub const VERTICAL: &'static str = "│";
     pub const HORIZONTAL: &'static str = "─";
     pub const EMPTY: &'static str = " ";
-}
\ No newline at end of file
+    pub const ELLIPSIS: &'static str = "…";
+    pub const SORT_ASCENDING: &'static str = "▲";
+    pub const SORT_DESCENDING: &'static str = "▼";
+    pub const TREE_BRANCH: &'static str = "├─";
+    pub const TREE_LAST_BRANCH: &'static str = "╰─";
+    pub const TREE_GUIDE: &'static str = "│ ";
+    pub const TREE_EMPTY: &'static str = "  ";
+    pub const TREE_COLLAPSED: &'static str = "▸";
+    pub const TREE_EXPANDED: &'static str = "▾";
+    pub const BLOCKS_HORIZONTAL: [&'static str; 8] = ["▏", "▎", "▍", "▌", "▋", "▊", "▉", "█"];
+    pub const BLOCKS_VERTICAL: [&'static str; 8] = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+}
@@ -0,0 +1,77 @@
+//! The module that contains the crate's [`Error`] type, and the [`Result`] type
+//! that all the fallible functions return.
+
+use std::fmt;
+use std::io;
+
+#[cfg(test)]
+/// The module containing tests for these structs
+mod test {
+
+    use super::*;
+
+    /* Tests for the `Error` enum */
+
+    #[test]
+    /// Test that [`Error`]s convert from [`io::Error`]s, and keep them as their
+    /// source
+    fn test_from_io() {
+        use std::error::Error as _;
+
+        let error = Error::from(io::Error::new(io::ErrorKind::BrokenPipe, "gone"));
+        assert!(matches!(error, Error::Io(_)));
+        assert_eq!(error.to_string(), "terminal IO failed: gone");
+        assert_eq!(error.source().unwrap().to_string(), "gone");
+    }
+}
+
+/// The type of the errors that the crate's functions return.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading from or writing to the terminal failed
+    Io(io::Error),
+    /// A widget was added to tabs that have no page to hold it
+    NoPage,
+    /// A widget was gridded into a column that its parent's grid doesn't have
+    NoSuchColumn(usize),
+    /// A widget was gridded into a row that its parent's grid doesn't have
+    NoSuchRow(usize),
+    /// The configured rows or columns of a grid add up to more than 100%; holds
+    /// the total percentage
+    PercentOver100(u16),
+    /// The size of the terminal couldn't be found out, e.g. because stdout isn't
+    /// a terminal
+    TerminalSize(io::Error),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "terminal IO failed: {}", error),
+            Error::NoPage => write!(f, "the tabs have no page to hold the widget"),
+            Error::NoSuchColumn(column) => write!(f, "the grid has no column {}", column),
+            Error::NoSuchRow(row) => write!(f, "the grid has no row {}", row),
+            Error::PercentOver100(percent) => {
+                write!(f, "the grid's rows or columns take up {}%, over 100%", percent)
+            },
+            Error::TerminalSize(error) => {
+                write!(f, "couldn't get the terminal size: {}", error)
+            },
+        }
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) | Error::TerminalSize(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+/// The result type returned by the crate's fallible functions
+pub type Result<T> = std::result::Result<T, Error>;
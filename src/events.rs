@@ -0,0 +1,344 @@
+//! The module that contains everything used for reacting to events: the
+//! [`Context`] that callbacks receive, the [`Callbacks`] that widgets keep
+//! their callbacks in, and the [`Timers`] that windows run callbacks with.
+
+use crossterm::event::*;
+
+use std::any::Any;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+/// The module containing tests for these structs
+mod test {
+
+    use super::*;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /* Tests for the `Callbacks` struct */
+
+    #[test]
+    /// Test that [`Callbacks::handle()`] only runs the matching callbacks
+    fn test_handle() {
+        let count = Rc::new(Cell::new(0));
+        let mut callbacks = Callbacks::default();
+        let c = count.clone();
+        callbacks.on_key(KeyCode::Enter, move |_| c.set(c.get() + 1));
+        let c = count.clone();
+        callbacks.on_click(move |_| c.set(c.get() + 10));
+
+        let mut ctx = Context::new(None);
+        let enter = Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let escape = Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(callbacks.handle(enter, &mut ctx));
+        assert!(!callbacks.handle(escape, &mut ctx));
+        assert_eq!(count.get(), 1);
+
+        // Keys only match with the modifiers they were added with, apart from
+        // Shift on characters
+        let c = count.clone();
+        let save = KeyEvent::new(KeyCode::Char('S'), KeyModifiers::CONTROL);
+        callbacks.on_key(save, move |_| c.set(c.get() + 100));
+        let ctrl_enter = Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL));
+        let shift_ctrl_s = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+        let shift_ctrl_s = Event::Key(KeyEvent::new(KeyCode::Char('S'), shift_ctrl_s));
+        let shift_s = Event::Key(KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT));
+        assert!(!callbacks.handle(ctrl_enter, &mut ctx));
+        assert!(!callbacks.handle(shift_s, &mut ctx));
+        assert!(callbacks.handle(shift_ctrl_s, &mut ctx));
+        assert_eq!(count.get(), 101);
+    }
+
+    /* Tests for the `Context` struct */
+
+    #[test]
+    /// Test the [`Context::state()`] method
+    fn test_state() {
+        let mut state: u32 = 1;
+        let mut ctx = Context::new(Some(&mut state));
+        *ctx.state::<u32>().unwrap() += 1;
+        assert!(ctx.state::<String>().is_none());
+        assert_eq!(state, 2);
+    }
+
+    /* Tests for the `Timers` struct */
+
+    #[test]
+    /// Test that [`Timers::run_due()`] runs one-shot timers once, repeating
+    /// timers every period, and no cancelled timers
+    fn test_timers() {
+        let count = Rc::new(Cell::new(0));
+        let mut timers = Timers::default();
+        let start = Instant::now();
+        let c = count.clone();
+        timers.add(start, Duration::from_secs(1), None, Box::new(move |_| c.set(c.get() + 1)));
+        let c = count.clone();
+        let period = Some(Duration::from_secs(2));
+        timers.add(start, Duration::from_secs(2), period, Box::new(move |_| c.set(c.get() + 10)));
+        let c = count.clone();
+        let cancel = Box::new(move |_: &mut Context| c.set(c.get() + 100));
+        let cancelled = timers.add(start, Duration::ZERO, None, cancel);
+        assert!(timers.cancel(cancelled));
+
+        let mut ctx = Context::new(None);
+        assert!(!timers.run_due(start, &mut ctx));
+        assert_eq!(timers.next_due(), Some(start + Duration::from_secs(1)));
+        assert!(timers.run_due(start + Duration::from_secs(1), &mut ctx));
+        assert!(timers.run_due(start + Duration::from_secs(4), &mut ctx));
+        assert_eq!(count.get(), 11);
+        assert_eq!(timers.next_due(), Some(start + Duration::from_secs(6)));
+
+        // Falling behind by a period and a half skips to the next one in step
+        assert!(timers.run_due(start + Duration::from_secs(9), &mut ctx));
+        assert_eq!(count.get(), 21);
+        assert_eq!(timers.next_due(), Some(start + Duration::from_secs(10)));
+    }
+
+    #[test]
+    /// Test that [`Timers::add()`] raises a zero period to [`MIN_PERIOD`], so the
+    /// timer keeps repeating
+    fn test_timers_zero_period() {
+        let count = Rc::new(Cell::new(0));
+        let mut timers = Timers::default();
+        let start = Instant::now();
+        let c = count.clone();
+        let callback = Box::new(move |_: &mut Context| c.set(c.get() + 1));
+        timers.add(start, Duration::ZERO, Some(Duration::ZERO), callback);
+
+        let mut ctx = Context::new(None);
+        assert!(timers.run_due(start, &mut ctx));
+        assert_eq!(timers.next_due(), Some(start + MIN_PERIOD));
+        assert!(timers.run_due(start + MIN_PERIOD, &mut ctx));
+        assert_eq!(count.get(), 2);
+    }
+}
+
+/// The type of the callbacks that run when the user interacts with a widget
+pub type Callback = Box<dyn FnMut(&mut Context)>;
+
+/// The type of the callbacks that run when the value of a widget changes. They
+/// also receive the new value.
+pub type ChangeCallback<T> = Box<dyn FnMut(&mut Context, &T)>;
+
+/// The collection of click and key callbacks of a widget.
+#[derive(Default)]
+pub struct Callbacks {
+    /// The callbacks run on a left click
+    click: Vec<Callback>,
+    /// The callbacks run when a key is pressed, along with that key and the
+    /// modifiers that must be held with it
+    keys: Vec<(KeyEvent, Callback)>,
+}
+impl Callbacks {
+
+    /// Run all the click callbacks, as if the widget was left-clicked. Returns
+    /// whether any callback was run.
+    pub fn click(&mut self, ctx: &mut Context) -> bool {
+        for callback in &mut self.click {
+            callback(ctx);
+        }
+        !self.click.is_empty()
+    }
+
+    /// Run all the callbacks that match `event`. Returns whether any callback
+    /// was run, i.e. whether the event was handled.
+    pub fn handle(&mut self, event: Event, ctx: &mut Context) -> bool {
+        let mut handled = false;
+        match event {
+            Event::Key(key) => {
+                for (bound, callback) in &mut self.keys {
+                    if bound.code == key.code && modifiers(bound) == modifiers(&key) {
+                        callback(ctx);
+                        handled = true;
+                    }
+                }
+            },
+            Event::Mouse(MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), .. }) => {
+                handled = self.click(ctx);
+            },
+            _ => {},
+        }
+        handled
+    }
+
+    /// Add a callback that is run when the widget is left-clicked
+    pub fn on_click(&mut self, callback: impl FnMut(&mut Context) + 'static) {
+        self.click.push(Box::new(callback));
+    }
+
+    /// Add a callback that is run when `key` is pressed while the widget gets
+    /// the key events. `key` is a [`KeyCode`], which only matches when no
+    /// modifiers are held, or a [`KeyEvent`] with the modifiers that must be. Shift
+    /// is ignored for characters, since it is already part of the character.
+    pub fn on_key(
+        &mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) {
+        self.keys.push((key.into(), Box::new(callback)));
+    }
+}
+
+/// A function that returns the modifiers held with `key` that callbacks are
+/// matched on, leaving out Shift for characters
+fn modifiers(key: &KeyEvent) -> KeyModifiers {
+    match key.code {
+        KeyCode::Char(_) => key.modifiers - KeyModifiers::SHIFT,
+        _ => key.modifiers,
+    }
+}
+
+/// The context passed to callbacks. It gives access to the application state
+/// stored in the window, and lets callbacks ask the window to redraw or quit.
+pub struct Context<'c> {
+    /// Whether the widget handling the event asked for the focus
+    focus_: bool,
+    /// How far from the end of the event's path the widget that asked for the
+    /// focus is, once it is known
+    focus_depth: Option<usize>,
+    /// Whether the window should quit once the event is handled
+    quit_: bool,
+    /// Whether the window should redraw once the event is handled
+    redraw_: bool,
+    /// The application state stored in the window, if there is one
+    state_: Option<&'c mut dyn Any>,
+}
+impl<'c> Context<'c> {
+
+    /// Create a new context giving access to `state`
+    pub fn new(state: Option<&'c mut dyn Any>) -> Context<'c> {
+        Context { focus_: false, focus_depth: None, quit_: false, redraw_: false, state_: state }
+    }
+
+    /// Ask the window to give the focus to the widget that is handling the event,
+    /// taking it away from its children. Containers use this when they hide the
+    /// child that had the focus.
+    pub fn focus(&mut self) {
+        self.focus_ = true;
+    }
+
+    /// Return how many widgets away from the end of the event's path the widget
+    /// that called [`Context::focus()`] is, if one did
+    pub fn focus_requested(&self) -> Option<usize> {
+        self.focus_depth
+    }
+
+    /// Record that the widget `depth` widgets away from the end of the event's
+    /// path just handled the event, if it asked for the focus
+    pub(crate) fn handled_at(&mut self, depth: usize) {
+        if self.focus_ && self.focus_depth.is_none() {
+            self.focus_depth = Some(depth);
+        }
+    }
+
+    /// Ask the window to quit once the event is handled
+    pub fn quit(&mut self) {
+        self.quit_ = true;
+    }
+
+    /// Return whether [`Context::quit()`] was called
+    pub fn quit_requested(&self) -> bool {
+        self.quit_
+    }
+
+    /// Ask the window to redraw once the event is handled. Windows always redraw
+    /// after an event was handled by a widget, so this is only needed when the
+    /// event wasn't handled.
+    pub fn redraw(&mut self) {
+        self.redraw_ = true;
+    }
+
+    /// Return whether [`Context::redraw()`] was called
+    pub fn redraw_requested(&self) -> bool {
+        self.redraw_
+    }
+
+    /// Return the application state stored in the window, if there is one and it
+    /// is of type `T`
+    pub fn state<T: Any>(&mut self) -> Option<&mut T> {
+        self.state_.as_deref_mut()?.downcast_mut::<T>()
+    }
+}
+
+/// The ID of a timer, used to cancel it
+pub type TimerId = u64;
+
+/// The shortest period a repeating timer can have; shorter periods, including
+/// zero, are raised to this, so that a repeating timer can't run in a busy loop
+pub const MIN_PERIOD: Duration = Duration::from_millis(1);
+
+/// A callback that runs after a delay, and then optionally every period
+struct Timer {
+    /// The callback run when the timer is due
+    callback: Callback,
+    /// When the timer is next due
+    due: Instant,
+    /// The ID of the timer
+    id: TimerId,
+    /// How often the timer repeats, if it does
+    period: Option<Duration>,
+}
+
+/// The collection of one-shot and repeating timers of a window.
+#[derive(Default)]
+pub struct Timers {
+    /// The ID that the next timer gets
+    next_id: TimerId,
+    /// All the timers that haven't finished yet
+    timers: Vec<Timer>,
+}
+impl Timers {
+
+    /// Add a timer that runs `callback` `delay` after `now`, and then every
+    /// `period` if there is one, but no more often than every [`MIN_PERIOD`].
+    /// Returns the ID of the new timer.
+    pub fn add(
+        &mut self,
+        now: Instant,
+        delay: Duration,
+        period: Option<Duration>,
+        callback: Callback
+    ) -> TimerId {
+        let id = self.next_id;
+        self.next_id += 1;
+        let period = period.map(|period| period.max(MIN_PERIOD));
+        self.timers.push(Timer { callback, due: now + delay, id, period });
+        id
+    }
+
+    /// Cancel the timer with the ID `id`. Returns whether there was such a timer.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != count
+    }
+
+    /// Return when the next timer is due, if there are any timers
+    pub fn next_due(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.due).min()
+    }
+
+    /// Run the callbacks of all the timers that are due at `now`. Repeating
+    /// timers are scheduled again, and the others are removed. Returns whether
+    /// any callback was run.
+    pub fn run_due(&mut self, now: Instant, ctx: &mut Context) -> bool {
+        let mut ran = false;
+        for timer in &mut self.timers {
+            if timer.due <= now {
+                (timer.callback)(ctx);
+                ran = true;
+
+                // A repeating timer that fell behind skips the periods it
+                // missed instead of running for each of them, staying in step
+                // with its original schedule
+                if let Some(period) = timer.period {
+                    let behind = (now - timer.due).as_nanos() % period.as_nanos();
+                    timer.due = now + period - Duration::from_nanos(behind as u64);
+                }
+            }
+        }
+        self.timers.retain(|timer| timer.due > now);
+        ran
+    }
+}
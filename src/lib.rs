@@ -2,13 +2,21 @@
 //! terminal manipulation library. Once more code gets written, documentation and
 //! examples will appear here.
 
+pub mod backend;
+pub mod buffer;
 pub mod constants;
+pub mod error;
+pub mod events;
 pub mod structure;
+pub mod text;
 pub mod traits;
 pub mod widgets;
 
+pub use error::{Error, Result};
+
 /// The `prelude` module for `rusty_panther`; contains all the necessary traits.
 pub mod prelude {
+    pub use crate::events::Context;
     pub use crate::structure::*;
     pub use crate::traits::*;
     pub use crate::widgets;
@@ -1,10 +1,15 @@
 //! The module that contains all the widget structure-related structs. This includes
 //! row/column/grid configuration structs and theme structs.
 
+use crate::constants::chars;
+use crate::error::{Error, Result};
+use crate::text;
 use crate::traits::Buildable;
 use crossterm::style::Color;
 use crossterm::terminal::size;
 
+use std::sync::atomic::{AtomicU64, Ordering};
+
 #[cfg(test)]
 /// The module containing tests for these structs
 mod test {
@@ -29,6 +34,90 @@ mod test {
         assert_eq!(grid.get_column_chars(2), 14);
     }
 
+    #[test]
+    /// Test the [`Grid::get_area_chars()`] method
+    fn test_get_area_chars() {
+
+        // Create the default grid for testing
+        let mut grid = Grid::builder()
+            .width(10)
+            .height(5)
+            .build();
+        grid.set_width_chars(150);
+        grid.set_height_chars(36);
+
+        assert_eq!(grid.get_area_chars(2, 3, 1, 1).unwrap(), (16, 14, 14, 7));
+        assert_eq!(grid.get_area_chars(2, 3, 3, 2).unwrap(), (16, 14, 44, 15));
+        assert_eq!(grid.get_area_chars(9, 5, 4, 4).unwrap(), (121, 29, 28, 6));
+        assert!(matches!(grid.get_area_chars(11, 1, 1, 1), Err(Error::NoSuchColumn(11))));
+        assert!(matches!(grid.get_area_chars(1, 0, 1, 1), Err(Error::NoSuchRow(0))));
+    }
+
+    #[test]
+    /// Test that [`Grid::row_configure()`] and [`Grid::column_configure()`] reject
+    /// rows and columns that don't exist, and percentages over 100%
+    fn test_configure() {
+        let mut grid = Grid::builder()
+            .width(4)
+            .height(2)
+            .build();
+        grid.row_configure(0, 70).unwrap();
+        assert_eq!((grid.rows[0].0, grid.rows[1].0), (70, 30));
+        assert!(matches!(grid.row_configure(2, 10), Err(Error::NoSuchRow(2))));
+        assert!(matches!(grid.row_configure(1, 40), Err(Error::PercentOver100(110))));
+        assert_eq!((grid.rows[0].0, grid.rows[1].0), (70, 30));
+
+        grid.column_configure(3, 40).unwrap();
+        assert_eq!(grid.columns[0].0, 20);
+        assert!(matches!(grid.column_configure(4, 10), Err(Error::NoSuchColumn(4))));
+    }
+
+    #[test]
+    /// Test the [`Grid::get_column_span_chars()`] method
+    fn test_get_column_span_chars() {
+
+        // Create the default grid for testing
+        let mut grid = Grid::builder()
+            .width(10)
+            .height(5)
+            .build();
+        grid.set_width_chars(150);
+        grid.set_height_chars(36);
+
+        assert_eq!(grid.get_column_span_chars(1, 1), grid.get_column_chars(1));
+        assert_eq!(grid.get_column_span_chars(1, 2), 29);
+        assert_eq!(grid.get_column_span_chars(10, 3), grid.get_column_chars(10));
+    }
+
+    #[test]
+    /// Test that the spans of a grid with more columns and rows than characters
+    /// are empty, instead of underflowing
+    fn test_get_span_chars_small() {
+        let mut grid = Grid::builder()
+            .width(10)
+            .height(10)
+            .build();
+        grid.set_width_chars(4);
+        grid.set_height_chars(3);
+
+        assert_eq!(grid.get_column_span_chars(1, 1), 0);
+        assert_eq!(grid.get_column_span_chars(10, 1), 0);
+        assert_eq!(grid.get_row_span_chars(1, 1), 0);
+        assert_eq!(grid.get_row_span_chars(10, 1), 0);
+        assert_eq!(grid.get_area_chars(1, 1, 1, 1).unwrap(), (1, 1, 0, 0));
+    }
+
+    #[test]
+    /// Test that [`Grid::try_new()`] sizes the grid to the terminal, or fails if
+    /// there is no terminal
+    fn test_try_new() {
+        match (Grid::try_new(), size()) {
+            (Ok(grid), Ok(size)) => assert_eq!((grid.width_chars, grid.height_chars), size),
+            (Err(Error::TerminalSize(_)), Err(_)) => {},
+            (grid, _) => panic!("unexpected result: {:?}", grid.err()),
+        }
+    }
+
     #[test]
     /// Test the [`Grid::get_placement_chars()`] method
     fn test_get_placement_chars() {
@@ -114,13 +203,85 @@ mod test {
         assert_eq!(grid.percent_to_char_width(25), 37);
         assert_eq!(grid.percent_to_char_width(1), 1);
     }
+
+    /* Tests for the `ColumnWidth` enum */
+
+    #[test]
+    /// Test the [`ColumnWidth::resolve()`] method
+    fn test_column_width_resolve() {
+        let rules = [
+            ColumnWidth::Fixed(10),
+            ColumnWidth::Percent(25),
+            ColumnWidth::Fill,
+            ColumnWidth::Fill,
+        ];
+
+        // 3 chars go to the gaps between the columns, leaving 100
+        assert_eq!(ColumnWidth::resolve(&rules, 103), vec![10, 25, 32, 33]);
+        assert_eq!(ColumnWidth::resolve(&rules, 20), vec![10, 4, 1, 2]);
+    }
+
+    /* Tests for the `Align` and `VerticalAlign` enums */
+
+    #[test]
+    /// Test the [`Align::offset()`] and [`VerticalAlign::offset()`] methods
+    fn test_align_offset() {
+        assert_eq!(Align::Left.offset(3, 10), 0);
+        assert_eq!(Align::Center.offset(3, 10), 3);
+        assert_eq!(Align::Right.offset(3, 10), 7);
+        assert_eq!(Align::Right.offset(12, 10), 0);
+        assert_eq!(VerticalAlign::Middle.offset(2, 5), 1);
+        assert_eq!(VerticalAlign::Bottom.offset(2, 5), 3);
+    }
+
+    /* Tests for the `Overflow` enum */
+
+    #[test]
+    /// Test the [`Overflow::fit()`] method
+    fn test_overflow_fit() {
+        let lines = || vec![String::from("abcdef"), String::from("ab"), String::from("c")];
+        assert_eq!(Overflow::Clip.fit(lines(), 4, 2), vec!["abcd", "ab"]);
+        assert_eq!(Overflow::Ellipsis.fit(lines(), 4, 2), vec!["abc…", "ab…"]);
+        assert_eq!(Overflow::Ellipsis.fit(lines(), 2, 2), vec!["a…", "a…"]);
+        assert_eq!(Overflow::Ellipsis.fit(lines(), 6, 3), lines());
+        assert!(Overflow::Clip.fit(lines(), 0, 2).is_empty());
+    }
+
+    /* Tests for the `Wrap` enum */
+
+    #[test]
+    /// Test the [`Wrap::lines()`] method
+    fn test_wrap_lines() {
+        let text = "the quick brown\nfox jumped";
+        assert_eq!(Wrap::None.lines(text, 5), vec!["the quick brown", "fox jumped"]);
+        assert_eq!(
+            Wrap::Word.lines(text, 10),
+            vec!["the quick", "brown", "fox jumped"]
+        );
+        assert_eq!(Wrap::Word.lines("a verylongword", 4), vec!["a", "very", "long", "word"]);
+        assert_eq!(Wrap::Char.lines("abcdefg\n\nhi", 3), vec!["abc", "def", "g", "", "hi"]);
+        assert_eq!(Wrap::Char.lines("abcdefg", 0), vec!["abcdefg"]);
+    }
+
+    /* Tests for the `WidgetId` struct */
+
+    #[test]
+    /// Test that [`WidgetId::next()`] never returns the same ID twice
+    fn test_widget_id_next() {
+        let first = WidgetId::next();
+        let second = WidgetId::next();
+        assert_ne!(first, second);
+    }
 }
 
 /// This struct contains sizing data used in gridding widgets, including how many
 /// rows/columns a parent widget has, and how much of the grid those rows/columns
 /// take up.
 /// 
-/// Default width×height is 5×5.
+/// Default width×height is 5×5. [`Grid::new()`] and [`Grid::builder()`] never
+/// fail: they size the grid to the terminal, or make it 0×0 chars if the
+/// terminal's size can't be found out. Use [`Grid::try_new()`] to get an error
+/// instead. Either way, parents resize their grids to their area when drawn.
 pub struct Grid {
     /// A [`Vec<GridColumn>`] containing all of this grid's columns
     pub columns: Vec<GridColumn>,
@@ -137,26 +298,72 @@ pub struct Grid {
 }
 impl Grid {
 
-    /// Configure the size of a particular column, and set its priority to [`true`]
-    pub fn column_configure(&mut self, col: usize, percent: u8) {
+    /// Return a new default grid sized to the terminal, like [`Grid::new()`], but
+    /// fail instead of returning a 0×0 grid if the size of the terminal can't be
+    /// found out, e.g. because stdout isn't a terminal.
+    pub fn try_new() -> Result<Grid> {
+        let (width, height) = size().map_err(Error::TerminalSize)?;
+        let mut grid = Grid::new();
+        grid.set_width_chars(width);
+        grid.set_height_chars(height);
+        Ok(grid)
+    }
+
+    /// Configure the size of a particular column, and set its priority to [`true`].
+    /// Fails, leaving the grid as it was, if there is no column `col` or if the
+    /// configured columns would take up more than 100%.
+    pub fn column_configure(&mut self, col: usize, percent: u8) -> Result<()> {
+        let old = self.columns.get(col).ok_or(Error::NoSuchColumn(col))?.copy();
         self.columns[col] = GridColumn(percent, true);
-        self.recalculate();
+        self.recalculate().inspect_err(|_| self.columns[col] = old)
     }
 
     /// Return the size of column `column` in characters
     pub fn get_column_chars(&self, column: u8) -> u16 {
+        self.get_column_span_chars(column, 1)
+    }
+
+    /// Return the combined size, in characters, of the `colspan` columns starting
+    /// at column `column`. Spans running past the last column are cut off there,
+    /// and columns too small to hold anything are `0` characters wide.
+    pub fn get_column_span_chars(&self, column: u8, colspan: u8) -> u16 {
         let (x1, _) = self.get_placement_chars(column, 1);
-        if column < self.width_ {
-            println!("column: {}, column + 1: {}", column, column+1);
-            let (x2, _) = self.get_placement_chars(column+1, 0);
-            println!("x1: {}, x2: {}", x1, x2);
-            return x2 - x1 - 1
+        let next = column.saturating_add(colspan.max(1));
+        if next <= self.width_ {
+            let (x2, _) = self.get_placement_chars(next, 0);
+            x2.saturating_sub(x1 + 1)
         }
         else {
-            return self.width_chars - x1 - 1
+            self.width_chars.saturating_sub(x1 + 1)
         }
     }
 
+    /// Get the placement and size of the area covering `colspan` columns and
+    /// `rowspan` rows, starting at column `column` and row `row`, in characters.
+    /// Returns an `(x, y, width, height)` tuple, or an error if the grid has no
+    /// such column or row.
+    pub fn get_area_chars(
+        &self,
+        column: u8,
+        row: u8,
+        colspan: u8,
+        rowspan: u8
+    ) -> Result<(u16, u16, u16, u16)> {
+        if column == 0 || column > self.width_ {
+            return Err(Error::NoSuchColumn(column as usize));
+        }
+        if row == 0 || row > self.height_ {
+            return Err(Error::NoSuchRow(row as usize));
+        }
+        let (x, y) = self.get_placement_chars(column, row);
+        Ok((
+            x,
+            y,
+            self.get_column_span_chars(column, colspan),
+            self.get_row_span_chars(row, rowspan)
+        ))
+    }
+
     /// Get the placement of the character at the top left of column `column` and
     /// row `row`, in characters. Returns an `(x, y)` tuple.
     pub fn get_placement_chars(&self, column: u8, row: u8) -> (u16, u16) {
@@ -180,7 +387,7 @@ impl Grid {
         // their percents to `percent_x` if they aren't the first column.
         for c in 1..=column {
             if c > 1 {
-                percent_x += &self.columns[c as usize].0;
+                percent_x += &self.columns[(c - 2) as usize].0;
             }
         }
 
@@ -188,7 +395,7 @@ impl Grid {
         // their percents to `percent_y` if they aren't the first row.
         for r in 1..=row {
             if r > 1 {
-                percent_y += &self.rows[r as usize].0;
+                percent_y += &self.rows[(r - 2) as usize].0;
             }
         }
         
@@ -197,15 +404,21 @@ impl Grid {
 
     /// Return the size of row `row` in characters
     pub fn get_row_chars(&self, row: u8) -> u16 {
+        self.get_row_span_chars(row, 1)
+    }
+
+    /// Return the combined size, in characters, of the `rowspan` rows starting at
+    /// row `row`. Spans running past the last row are cut off there, and rows
+    /// too small to hold anything are `0` characters tall.
+    pub fn get_row_span_chars(&self, row: u8, rowspan: u8) -> u16 {
         let (_, y1) = self.get_placement_chars(0, row);
-        if row < self.height_ {
-            println!("row: {}, row + 1: {}", row, row+1);
-            let (_, y2) = self.get_placement_chars(0, row+1);
-            println!("y1: {}, y2: {}", y1, y2);
-            return y2 - y1
+        let next = row.saturating_add(rowspan.max(1));
+        if next <= self.height_ {
+            let (_, y2) = self.get_placement_chars(0, next);
+            y2.saturating_sub(y1)
         }
         else {
-            return self.height_chars - y1 - 1
+            self.height_chars.saturating_sub(y1 + 1)
         }
         /*
         let (x1, _) = self.get_placement_chars(column, 1);
@@ -237,11 +450,21 @@ impl Grid {
     }
 
     /// Recalculate the size of all the rows and columns based on which ones have
-    /// user-set percentates.
-    pub fn recalculate(&mut self) {
+    /// user-set percentates. Fails if the user-set rows or columns take up more
+    /// than 100%.
+    pub fn recalculate(&mut self) -> Result<()> {
 
         // First calculate the rows, giving prioritized rows the priority
 
+        // The configured rows can't take up more than the whole grid
+        let configured: u16 = self.rows.iter()
+            .filter(|row| row.1)
+            .map(|row| row.0 as u16)
+            .sum();
+        if configured > 100 {
+            return Err(Error::PercentOver100(configured));
+        }
+
         /* Loop over all the rows, subtracting the prioritized rows' percent from
         the available room percentage, and subtracting `1` from the number of
         total rows. This leaves us with the percent that the unprioritized rows
@@ -259,17 +482,24 @@ impl Grid {
 
         // Now go through all the UNprioritized rows and divide the remaing
         // percent up between them
-        let percent_for_rows = row_p / rows as u8;
-        let mut i = 0;
-        for row in &self.rows.clone() {
+        let percent_for_rows = row_p / rows.max(1) as u8;
+        for (i, row) in self.rows.clone().iter().enumerate() {
             if !row.1 {
                 self.rows[i] = GridRow(percent_for_rows, false);
             }
-            i += 1;
         }
 
         // Now calculate the columns, giving prioritized columns the priority
 
+        // The configured columns can't take up more than the whole grid
+        let configured: u16 = self.columns.iter()
+            .filter(|column| column.1)
+            .map(|column| column.0 as u16)
+            .sum();
+        if configured > 100 {
+            return Err(Error::PercentOver100(configured));
+        }
+
         /* Loop over all the columns, subtracting the prioritized columns' percent from
         the available room percentage, and subtracting `1` from the number of
         total columns. This leaves us with the percent that the unprioritized columns
@@ -287,20 +517,22 @@ impl Grid {
 
         // Now go through all the UNprioritized columns and divide the remaing
         // percent up between them
-        let percent_for_columns = column_p / columns as u8;
-        let mut i = 0;
-        for column in &self.columns.clone() {
+        let percent_for_columns = column_p / columns.max(1) as u8;
+        for (i, column) in self.columns.clone().iter().enumerate() {
             if !column.1 {
                 self.columns[i] = GridColumn(percent_for_columns, false);
             }
-            i += 1;
         }
+        Ok(())
     }
 
-    /// Configure the size of a particular row, and set its priority to [`true`]
-    pub fn row_configure(&mut self, row: usize, percent: u8) {
+    /// Configure the size of a particular row, and set its priority to [`true`].
+    /// Fails, leaving the grid as it was, if there is no row `row` or if the
+    /// configured rows would take up more than 100%.
+    pub fn row_configure(&mut self, row: usize, percent: u8) -> Result<()> {
+        let old = self.rows.get(row).ok_or(Error::NoSuchRow(row))?.copy();
         self.rows[row] = GridRow(percent, true);
-        self.recalculate();
+        self.recalculate().inspect_err(|_| self.rows[row] = old)
     }
 
     /// Set the height of the grid in characters. NOT a builder method.
@@ -324,7 +556,7 @@ impl Grid {
 
         // Re-configure the list of rows based on the height given, calculating
         // the new row-size percent
-        let percent: u8 = 100 / self.height_;
+        let percent: u8 = 100 / self.height_.max(1);
         self.rows = Vec::new();
         for _ in 0..self.height_ { self.rows.push(GridRow(percent, false)) }
         self
@@ -338,7 +570,7 @@ impl Grid {
 
         // Re-configure the list of columns based on the width given, calculating
         // the new column-size percent
-        let percent: u8 = 100 / self.width_;
+        let percent: u8 = 100 / self.width_.max(1);
         self.columns = Vec::new();
         for _ in 0..self.width_ { self.columns.push(GridColumn(percent, false)) }
         self
@@ -357,24 +589,235 @@ impl Buildable for Grid {
         }
     }
 
+    /// Return a default grid sized to the terminal, to customize. Never fails;
+    /// the grid is 0×0 chars if the terminal's size can't be found out, which
+    /// [`Grid::try_new()`] reports as an error instead.
     fn builder() -> Grid {
         let col = GridColumn(20, false);
         let row = GridRow(20, false);
+        let (width_chars, height_chars) = size().unwrap_or((0, 0));
         Grid {
             columns: vec![col.copy(), col.copy(), col.copy(), col.copy(), col.copy()],
             rows: vec![row.copy(), row.copy(), row.copy(), row.copy(), row.copy()],
             height_: 5,
-            height_chars: size().expect("size()").1,
+            height_chars,
             width_: 5,
-            width_chars: size().expect("size()").0,
+            width_chars,
         }
     }
 
+    /// Return a default grid sized to the terminal, like [`Grid::builder()`],
+    /// so it is 0×0 chars if the terminal's size can't be found out
     fn new() -> Grid {
         Grid::builder().build()
     }
 }
 
+/// The width rule of a column of a table.
+/// 
+/// Like [`GridColumn`]s, columns with a set size get their room first, and the
+/// [`ColumnWidth::Fill`] columns split whatever is left evenly between them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnWidth {
+    /// A fixed width, in chars
+    Fixed(u16),
+    /// A percentage of the width of the table
+    Percent(u8),
+    /// An even share of the width left over by the other columns
+    Fill,
+}
+impl ColumnWidth {
+
+    /// Return the width in chars of each column in `rules`, for columns that are
+    /// `width` chars wide all together, including a 1 char gap between them
+    pub fn resolve(rules: &[ColumnWidth], width: u16) -> Vec<u16> {
+
+        // Give the fixed and percent columns their room first
+        let room = width.saturating_sub(rules.len().saturating_sub(1) as u16);
+        let mut widths: Vec<u16> = rules
+            .iter()
+            .map(|rule| match rule {
+                ColumnWidth::Fixed(chars) => *chars,
+                ColumnWidth::Percent(percent) => {
+                    ((room as f32 / 100f32) * *percent as f32) as u16
+                },
+                ColumnWidth::Fill => 0,
+            })
+            .collect();
+
+        // Now divide the remaining room up between the fill columns, giving any
+        // leftover chars to the last one
+        let fills = rules.iter().filter(|rule| **rule == ColumnWidth::Fill).count() as u16;
+        if fills > 0 {
+            let left = room.saturating_sub(widths.iter().sum());
+            let mut extra = left % fills;
+            for (i, rule) in rules.iter().enumerate().rev() {
+                if *rule == ColumnWidth::Fill {
+                    widths[i] = left / fills + extra;
+                    extra = 0;
+                }
+            }
+        }
+        widths
+    }
+}
+
+/// The horizontal alignment of the lines of text in a widget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    /// Lines start at the left edge of the widget
+    Left,
+    /// Lines are centered, leaning to the left if they can't be exactly
+    Center,
+    /// Lines end at the right edge of the widget
+    Right,
+}
+impl Align {
+
+    /// Return how far from the left edge a line `length` cells wide starts, in a
+    /// widget `width` cells wide
+    pub fn offset(&self, length: u16, width: u16) -> u16 {
+        let room = width.saturating_sub(length);
+        match self {
+            Align::Left => 0,
+            Align::Center => room / 2,
+            Align::Right => room,
+        }
+    }
+}
+
+/// What happens to text that doesn't fit into a widget, after wrapping.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overflow {
+    /// The text is simply cut off
+    Clip,
+    /// The text is cut off, and an ellipsis shows where
+    Ellipsis,
+}
+impl Overflow {
+
+    /// Return `lines` cut down to at most `height` lines of at most `width` cells
+    /// each
+    pub fn fit(&self, mut lines: Vec<String>, width: usize, height: usize) -> Vec<String> {
+        if width == 0 || height == 0 {
+            return Vec::new();
+        }
+        let cut = lines.len() > height;
+        lines.truncate(height);
+        for line in &mut lines {
+            if text::width(line) > width {
+                *line = self.cut(line, width);
+            }
+        }
+
+        // The last line shows that there are more lines that didn't fit
+        if let (Overflow::Ellipsis, true, Some(last)) = (self, cut, lines.last_mut()) {
+            if text::width(last) < width {
+                last.push_str(chars::ELLIPSIS);
+            }
+            else if !last.ends_with(chars::ELLIPSIS) {
+                *last = self.cut(last, width);
+            }
+        }
+        lines
+    }
+
+    /// Return `line` cut off to `width` cells, ending with an ellipsis for
+    /// [`Overflow::Ellipsis`]
+    fn cut(&self, line: &str, width: usize) -> String {
+        match self {
+            Overflow::Clip => text::clip(line, width).to_string(),
+            Overflow::Ellipsis => text::clip(line, width - 1).to_string() + chars::ELLIPSIS,
+        }
+    }
+}
+
+/// The vertical alignment of the lines of text in a widget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerticalAlign {
+    /// The lines start at the top of the widget
+    Top,
+    /// The lines are centered, leaning to the top if they can't be exactly
+    Middle,
+    /// The lines end at the bottom of the widget
+    Bottom,
+}
+impl VerticalAlign {
+
+    /// Return how far from the top the first of `lines` lines of text is, in a
+    /// widget `height` lines tall
+    pub fn offset(&self, lines: u16, height: u16) -> u16 {
+        let room = height.saturating_sub(lines);
+        match self {
+            VerticalAlign::Top => 0,
+            VerticalAlign::Middle => room / 2,
+            VerticalAlign::Bottom => room,
+        }
+    }
+}
+
+/// How text is broken up into lines. A newline in the text always starts a new
+/// line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Wrap {
+    /// Lines are only broken at newlines, however long they are
+    None,
+    /// Lines are broken between words; words longer than a line are broken
+    /// wherever they have to be
+    Word,
+    /// Lines are broken after as many graphemes as fit
+    Char,
+}
+impl Wrap {
+
+    /// Return the lines of `text`, broken up to fit into `width` cells. A `width`
+    /// of `0` means there is no limit.
+    pub fn lines(&self, text: &str, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            match self {
+                _ if width == 0 => lines.push(paragraph.to_string()),
+                Wrap::None => lines.push(paragraph.to_string()),
+                Wrap::Word => Wrap::words(paragraph, width, &mut lines),
+                Wrap::Char => {
+                    lines.extend(text::split(paragraph, width).into_iter().map(String::from));
+                },
+            }
+        }
+        lines
+    }
+
+    /// Add the lines of `paragraph`, broken between words to fit into `width`
+    /// cells, to `lines`
+    fn words(paragraph: &str, width: usize, lines: &mut Vec<String>) {
+        let mut line = String::new();
+        let mut length = 0;
+        for word in paragraph.split_whitespace() {
+            let word_length = text::width(word);
+
+            // The word fits onto the current line after a space
+            if length > 0 && length + 1 + word_length <= width {
+                line.push(' ');
+                line.push_str(word);
+                length += 1 + word_length;
+                continue;
+            }
+
+            // Otherwise it starts a new line, breaking it up if it doesn't fit
+            // onto a line of its own
+            if length > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            let mut pieces = text::split(word, width);
+            let last = pieces.pop().unwrap_or_default();
+            lines.extend(pieces.into_iter().map(String::from));
+            length = text::width(last);
+            line = last.to_string();
+        }
+        lines.push(line);
+    }
+}
+
 /// The struct for storing a grid column's data.
 /// 
 /// The [`u8`] is the percentage of the grid's width that this column will take
@@ -407,6 +850,16 @@ impl GridRow {
 pub struct Theme {
     pub fg: (u8, u8, u8),
     pub bg: (u8, u8, u8),
+    /// The color used to highlight the widget that has the focus
+    pub focus: (u8, u8, u8),
+    /// The color used to highlight the widget under the mouse pointer
+    pub hover: (u8, u8, u8),
+    /// The background color of a widget while it is being pressed
+    pub pressed: (u8, u8, u8),
+    /// The background color of selected items, e.g. in a list
+    pub selected: (u8, u8, u8),
+    /// The background color of every other row, e.g. in a striped table
+    pub stripe: (u8, u8, u8),
 }
 impl Theme {
 
@@ -425,6 +878,13 @@ impl Theme {
         self
     }
 
+    /// Set the focus highlight color to the RGB value `focus` of type
+    /// [`(u8, u8, u8)`].
+    pub fn focus_rgb(mut self, focus: (u8, u8, u8)) -> Theme {
+        self.focus = focus;
+        self
+    }
+
     /// Get the background color of this theme as an [`Rgb`]
     pub fn get_bg_rgb(&self) -> Color {
         Color::Rgb { r: self.bg.0, g: self.bg.1, b: self.bg.2 }
@@ -434,15 +894,84 @@ impl Theme {
     pub fn get_fg_rgb(&self) -> Color {
         Color::Rgb { r: self.fg.0, g: self.fg.1, b: self.fg.2 }
     }
+
+    /// Get the focus highlight color of this theme as an [`Rgb`].
+    pub fn get_focus_rgb(&self) -> Color {
+        Color::Rgb { r: self.focus.0, g: self.focus.1, b: self.focus.2 }
+    }
+
+    /// Get the hover highlight color of this theme as an [`Rgb`].
+    pub fn get_hover_rgb(&self) -> Color {
+        Color::Rgb { r: self.hover.0, g: self.hover.1, b: self.hover.2 }
+    }
+
+    /// Get the pressed background color of this theme as an [`Rgb`].
+    pub fn get_pressed_rgb(&self) -> Color {
+        Color::Rgb { r: self.pressed.0, g: self.pressed.1, b: self.pressed.2 }
+    }
+
+    /// Get the selected item background color of this theme as an [`Rgb`].
+    pub fn get_selected_rgb(&self) -> Color {
+        Color::Rgb { r: self.selected.0, g: self.selected.1, b: self.selected.2 }
+    }
+
+    /// Get the stripe background color of this theme as an [`Rgb`].
+    pub fn get_stripe_rgb(&self) -> Color {
+        Color::Rgb { r: self.stripe.0, g: self.stripe.1, b: self.stripe.2 }
+    }
+
+    /// Set the hover highlight color to the RGB value `hover` of type
+    /// [`(u8, u8, u8)`].
+    pub fn hover_rgb(mut self, hover: (u8, u8, u8)) -> Theme {
+        self.hover = hover;
+        self
+    }
+
+    /// Set the pressed background color to the RGB value `pressed` of type
+    /// [`(u8, u8, u8)`].
+    pub fn pressed_rgb(mut self, pressed: (u8, u8, u8)) -> Theme {
+        self.pressed = pressed;
+        self
+    }
+
+    /// Set the selected item background color to the RGB value `selected` of
+    /// type [`(u8, u8, u8)`].
+    pub fn selected_rgb(mut self, selected: (u8, u8, u8)) -> Theme {
+        self.selected = selected;
+        self
+    }
+
+    /// Set the stripe background color to the RGB value `stripe` of type
+    /// [`(u8, u8, u8)`].
+    pub fn stripe_rgb(mut self, stripe: (u8, u8, u8)) -> Theme {
+        self.stripe = stripe;
+        self
+    }
 }
 impl Buildable for Theme {
 
     fn build(self) -> Theme {
-        Theme { fg: self.fg, bg: self.bg }
+        Theme {
+            fg: self.fg,
+            bg: self.bg,
+            focus: self.focus,
+            hover: self.hover,
+            pressed: self.pressed,
+            selected: self.selected,
+            stripe: self.stripe,
+        }
     }
 
     fn builder() -> Theme {
-        Theme { fg: (255, 255, 255), bg: (0, 0, 0) }
+        Theme {
+            fg: (255, 255, 255),
+            bg: (0, 0, 0),
+            focus: (97, 175, 239),
+            hover: (229, 192, 123),
+            pressed: (62, 68, 81),
+            selected: (44, 80, 122),
+            stripe: (30, 33, 39),
+        }
     }
 
     fn new() -> Theme {
@@ -450,6 +979,23 @@ impl Buildable for Theme {
     }
 }
 
+/// The stable ID of a widget added to a parent, returned by
+/// [`Parent::add()`](crate::traits::Parent::add) and
+/// [`Parent::grid()`](crate::traits::Parent::grid).
+///
+/// The ID stays the same for as long as the widget lives, even if it is moved to
+/// another parent, and can be used to reach the widget through its window, e.g.
+/// with [`Window::get()`](crate::widgets::Window::get).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct WidgetId(u64);
+impl WidgetId {
+    /// Return a new ID, different from every other ID returned so far
+    pub(crate) fn next() -> WidgetId {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        WidgetId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 /// Returns the default theme
 pub fn default_theme() -> Theme {
     Theme::new()
@@ -0,0 +1,133 @@
+//! The module that contains the text measurement functions. Terminals show text
+//! as grapheme clusters (what the user sees as a single character, even if it is
+//! made up of several `char`s), each taking up one or two cells of the screen;
+//! all the widgets measure, clip and place their text with these functions
+//! instead of counting bytes or `char`s.
+
+use crate::constants::chars;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Return the byte index in `text` of the grapheme at position `position`, or
+/// the length of `text` if it has fewer graphemes
+pub fn byte_index(text: &str, position: usize) -> usize {
+    text.grapheme_indices(true).nth(position).map_or(text.len(), |(i, _)| i)
+}
+
+/// Return the longest start of `text` that takes up at most `width` cells
+pub fn clip(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, grapheme) in text.grapheme_indices(true) {
+        used += grapheme_width(grapheme);
+        if used > width {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// Return the number of graphemes in `text`
+pub fn count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Return the number of cells that `grapheme`, a single grapheme cluster, takes
+/// up on the screen: `0`, `1` or `2`
+pub fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().min(2)
+}
+
+/// Return an iterator over the graphemes of `text`
+pub fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    text.graphemes(true)
+}
+
+/// Return whether `grapheme`, a single grapheme cluster, is whitespace
+pub fn is_whitespace(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/// Return `text` split into pieces of at most `width` cells each, or a single
+/// empty piece if `text` is empty. A grapheme wider than `width` still gets a
+/// piece of its own.
+pub fn split(text: &str, width: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let (mut start, mut used) = (0, 0);
+    for (i, grapheme) in text.grapheme_indices(true) {
+        let grapheme_width = grapheme_width(grapheme);
+        if used + grapheme_width > width && i > start {
+            pieces.push(&text[start..i]);
+            (start, used) = (i, 0);
+        }
+        used += grapheme_width;
+    }
+    pieces.push(&text[start..]);
+    pieces
+}
+
+/// Return `text` cut off to at most `width` cells, ending with an ellipsis if
+/// anything was cut off
+pub fn truncate(text: &str, width: usize) -> String {
+    if self::width(text) <= width {
+        text.to_string()
+    }
+    else if width == 0 {
+        String::new()
+    }
+    else {
+        clip(text, width - 1).to_string() + chars::ELLIPSIS
+    }
+}
+
+/// Return the number of cells that `text` takes up on the screen
+pub fn width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+#[cfg(test)]
+/// The module containing tests for these functions
+mod test {
+
+    use super::*;
+
+    #[test]
+    /// Test the [`width()`] and [`count()`] functions
+    fn test_width() {
+        assert_eq!(width("abc"), 3);
+        assert_eq!(width("日本語"), 6);
+        assert_eq!(width("╭─╮"), 3);
+        assert_eq!(width("e\u{301}"), 1);
+        assert_eq!(count("e\u{301}"), 1);
+        assert_eq!(count("👍🏽!"), 2);
+        assert_eq!(width("👍🏽!"), 3);
+    }
+
+    #[test]
+    /// Test the [`byte_index()`] and [`clip()`] functions
+    fn test_clip() {
+        assert_eq!(byte_index("a日b", 2), 4);
+        assert_eq!(byte_index("a日b", 5), 5);
+        assert_eq!(clip("a日b", 2), "a");
+        assert_eq!(clip("a日b", 3), "a日");
+        assert_eq!(clip("e\u{301}x", 1), "e\u{301}");
+    }
+
+    #[test]
+    /// Test the [`split()`] function
+    fn test_split() {
+        assert_eq!(split("abcde", 2), vec!["ab", "cd", "e"]);
+        assert_eq!(split("日本語", 3), vec!["日", "本", "語"]);
+        assert_eq!(split("a日本", 4), vec!["a日", "本"]);
+        assert_eq!(split("", 4), vec![""]);
+    }
+
+    #[test]
+    /// Test the [`truncate()`] function
+    fn test_truncate() {
+        assert_eq!(truncate("abc", 3), "abc");
+        assert_eq!(truncate("日本語", 5), "日本…");
+        assert_eq!(truncate("日本語", 4), "日…");
+        assert_eq!(truncate("abc", 0), "");
+    }
+}
@@ -1,6 +1,55 @@
 //! The crate that contains all the traits, for better organization and easier use.
 
+use crate::buffer::Buffer;
+use crate::error::Result;
+use crate::events::Context;
+use crate::structure::WidgetId;
+
 use crossterm::event::*;
+use crossterm::style::{Attributes, Color};
+
+use std::any::Any;
+use std::io;
+
+/// The trait for rendering backends; that is, whatever the window's frames are
+/// finally written to. A backend owns cursor movement, colors and printing.
+pub trait Backend {
+
+    /// Clears the whole screen
+    fn clear(&mut self) -> io::Result<()>;
+
+    /// Called when the window starts using this backend, e.g. to enter raw mode
+    /// and the alternate screen. Does nothing by default.
+    fn enter(&mut self) -> io::Result<()> { Ok(()) }
+
+    /// Makes sure everything printed so far actually reaches the screen
+    fn flush(&mut self) -> io::Result<()>;
+
+    /// Called when the window stops using this backend; undoes whatever
+    /// [`Backend::enter()`] did. Does nothing by default.
+    fn leave(&mut self) -> io::Result<()> { Ok(()) }
+
+    /// Moves the cursor to (`x`, `y`)
+    fn move_to(&mut self, x: u16, y: u16) -> io::Result<()>;
+
+    /// Prints `text` at the cursor, moving the cursor along
+    fn print(&mut self, text: &str) -> io::Result<()>;
+
+    /// Resets the colors and attributes to the screen's defaults
+    fn reset_style(&mut self) -> io::Result<()>;
+
+    /// Sets the attributes used for printing to `attributes`
+    fn set_attributes(&mut self, attributes: Attributes) -> io::Result<()>;
+
+    /// Sets the background color used for printing to `color`
+    fn set_bg(&mut self, color: Color) -> io::Result<()>;
+
+    /// Sets the foreground color used for printing to `color`
+    fn set_fg(&mut self, color: Color) -> io::Result<()>;
+
+    /// Returns the size of the screen, as a `(width, height)` tuple
+    fn size(&self) -> io::Result<(u16, u16)>;
+}
 
 /// The trait for all structs that can be built using the builder pattern syntax.
 pub trait Buildable {
@@ -21,48 +70,149 @@ pub trait Buildable {
 pub trait Eventable {
 
     /// Called with an event parameter to trigger a callback on the widget. Returns
-    /// [`false`] if there is no callback for this event.
-    fn event_send(self, event: Event) -> bool;
+    /// [`false`] if there is no callback for this event, in which case the event
+    /// is passed on ("bubbled up") to the widget's parent.
+    /// 
+    /// Key events are sent to the focused widget, and mouse events to the widget
+    /// under the pointer. `ctx` gives access to the application state, and lets
+    /// the widget ask the window to redraw or quit.
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool;
+
+    /// Called on every tick of the window's tick rate, on every widget, e.g. to
+    /// move an animation along. Returns whether the widget changed and needs to
+    /// be redrawn; does nothing and returns [`false`] by default.
+    fn tick(&mut self, _ctx: &mut Context) -> bool { false }
 }
 
 /// This trait is for any widgets that can take focus. Note that THIS IS REQUIRED
 /// for widgets that take keyboard input. It adds methods for adding and removing
 /// focus.
+/// 
+/// The window keeps all the focusable widgets in a focus chain, in the order they
+/// were added; Tab and Shift+Tab move the focus along it, and clicking on a
+/// focusable widget gives it the focus.
 pub trait Focusable {
 
     /// Called when this widget is to have the focus
-    fn focus_add(self);
+    fn focus_add(&mut self);
 
     /// Called when this widget is to have its focus taken away
-    fn focus_remove(self);
+    fn focus_remove(&mut self);
 }
 
-/// The trait for any widgets that are parents; that is, they contain child widgets.
-pub trait Parent<'a> {
-
-    /// Adds a child widget to this parent widget. Takes placement X and placement
-    /// Y arguments. Note that this doesn't affect widgets added by `grid()`,
-    /// and hence widgets added with `add()` don't get scaled by the resizing of
-    /// the terminal.
-    fn add(&mut self, child: Box<&'a mut dyn Widget>, x: u16, y: u16);
-
-    /// Adds a child widget to this parent widget. Takes row, column, rowspan, and
-    /// columnspan arguments for where and how to place this widget.
+/// The trait for any widgets that are parents; that is, they own child widgets.
+/// 
+/// Every child gets a [`WidgetId`] when it is added, which can later be used to
+/// reach it, remove it, or move it to another parent.
+pub trait Parent {
+
+    /// Adds a child widget to this parent widget, returning its new ID. Takes
+    /// placement X and placement Y arguments. Note that this doesn't affect
+    /// widgets added by `grid()`, and hence widgets added with `add()` don't get
+    /// scaled by the resizing of the terminal.
+    fn add(&mut self, child: Box<dyn Widget>, x: u16, y: u16) -> WidgetId {
+        let id = WidgetId::next();
+        self.add_with_id(id, child, x, y);
+        id
+    }
+
+    /// Like [`Parent::add()`], but keeps the already existing ID `id` for the
+    /// child; used when a widget is moved from one parent to another.
+    fn add_with_id(&mut self, id: WidgetId, child: Box<dyn Widget>, x: u16, y: u16);
+
+    /// Returns whether this parent can take children right now, e.g. [`false`]
+    /// for tabs without any pages. Returns [`true`] by default.
+    fn can_hold(&self) -> bool { true }
+
+    /// Adds a child widget to this parent widget, returning its new ID. Takes
+    /// row, column, rowspan, and columnspan arguments for where and how to place
+    /// this widget.
     fn grid(
-        &mut self, child: Box<&'a mut dyn Widget>,
+        &mut self, child: Box<dyn Widget>,
+        row: u16,
+        col: u16,
+        rowspan: u16,
+        colspan: u16
+    ) -> WidgetId {
+        let id = WidgetId::next();
+        self.grid_with_id(id, child, row, col, rowspan, colspan);
+        id
+    }
+
+    /// Like [`Parent::grid()`], but keeps the already existing ID `id` for the
+    /// child; used when a widget is moved from one parent to another.
+    fn grid_with_id(
+        &mut self,
+        id: WidgetId,
+        child: Box<dyn Widget>,
         row: u16,
         col: u16,
         rowspan: u16,
         colspan: u16
     );
-}
 
-/// The trait for widget structs.
-pub trait Widget {
+    /// Removes the widget with the ID `id` from this parent or from any of its
+    /// descendants, returning it, or [`None`] if there is no such widget.
+    fn remove(&mut self, id: WidgetId) -> Option<Box<dyn Widget>>;
+}
 
-    /// Draws the widget, with parameters location (`x`, `y`) and size `width`×`height`.
-    /// This function is called by the parent widgets.
-    fn draw(&mut self, x: u16, y: u16, width: u16, height: u16);
+/// The trait for widget structs. Widgets are [`Any`], so that they can be
+/// downcast back to their own type, e.g. by [`Window::get()`].
+/// 
+/// [`Window::get()`]: crate::widgets::Window::get
+pub trait Widget: Any {
+
+    /// Returns this widget as an [`Eventable`], if it takes events. Returns
+    /// [`None`] by default.
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { None }
+
+    /// Returns this widget as a [`Focusable`], if it can currently take the focus.
+    /// Returns [`None`] by default.
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { None }
+
+    /// Returns this widget as a [`Parent`], if it has children. Returns [`None`]
+    /// by default.
+    fn as_parent(&mut self) -> Option<&mut dyn Parent> { None }
+
+    /// Returns all the immediate children of this widget, if it is a parent, in
+    /// the order they are drawn. Returns an empty [`Vec`] by default.
+    fn children_mut(&mut self) -> Vec<&mut dyn Widget> { Vec::new() }
+
+    /// Draws the widget into `buffer`, with parameters location (`x`, `y`) and size
+    /// `width`×`height`. This function is called by the parent widgets. A `width`
+    /// or `height` of `0` means the widget should use its natural size. Fails if
+    /// a child can't be laid out, e.g. because it is outside its parent's grid.
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()>;
+
+    /// Draws the widget into `buffer` again, in the same place and at the same size
+    /// as the last time it was drawn, if it changed since then, e.g. because its
+    /// text was set. Returns whether it was drawn; does nothing and returns
+    /// [`false`] by default.
+    fn draw_dirty(&mut self, _buffer: &mut Buffer) -> Result<bool> { Ok(false) }
+
+    /// Returns the descendant of this widget with the ID `id`, if there is one.
+    /// Returns [`None`] by default.
+    fn find(&self, _id: WidgetId) -> Option<&(dyn Widget + 'static)> { None }
+
+    /// Returns the descendant of this widget with the ID `id` mutably, if there
+    /// is one. Returns [`None`] by default.
+    fn find_mut(&mut self, _id: WidgetId) -> Option<&mut (dyn Widget + 'static)> {
+        None
+    }
+
+    /// Get the area the widget was last drawn in, as an `(x, y, width, height)`
+    /// tuple, in characters
+    fn get_area(&self) -> (u16, u16, u16, u16);
+
+    /// Get the `(rowspan, colspan)` of the child, in grid units
+    fn get_span(&self) -> (u16, u16);
 
     /// Get the `x` postition of the child, either in characters or in grid units
     fn get_x(&self) -> u16;
@@ -70,9 +220,25 @@ pub trait Widget {
     /// Get the `y` position of the child, either in characters or in grid units
     fn get_y(&self) -> u16;
 
+    /// Called when the mouse pointer moves onto this widget. Does nothing by
+    /// default.
+    fn hover_add(&mut self) {}
+
+    /// Called when the mouse pointer moves off of this widget. Does nothing by
+    /// default.
+    fn hover_remove(&mut self) {}
+
+    /// Set the `rowspan` and `colspan` of the child, in grid units
+    fn set_span(&mut self, rowspan: u16, colspan: u16);
+
     /// Set the `x` position of the child, either in characters or in grid units
     fn set_x(&mut self, x: u16);
 
     /// Set the `y` position of the child, either in characters or in grid units
     fn set_y(&mut self, y: u16);
+
+    /// Called instead of [`Widget::draw()`] when the parent had no room to draw
+    /// the widget, so that it isn't drawn again where it was before, e.g. by
+    /// [`Widget::draw_dirty()`]. Does nothing by default.
+    fn skip_draw(&mut self) {}
 }
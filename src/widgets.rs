@@ -1,77 +1,126 @@
 //! The module that contains all the widgets used in creating GUIs.
 
+use crate::backend::{self, BackendGuard, CrosstermBackend, MemoryBackend};
+use crate::buffer::{Buffer, Cell};
 use crate::constants::chars;
+use crate::error::{Error, Result};
+use crate::events::{
+    Callbacks,
+    ChangeCallback,
+    Context,
+    TimerId,
+    Timers,
+};
 use crate::structure::*;
+use crate::text;
 use crate::traits::*;
 
-use crossterm::{cursor, execute};
 use crossterm::event::*;
-use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
-use crossterm::terminal::*;
+use crossterm::style::{Attribute, Attributes, Color};
 
-use std::io::{stdout, Write};
+#[cfg(feature = "tokio")]
+use futures_util::{Stream, StreamExt};
+
+use std::any::Any;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
 /// A function that creates a border box
-fn create_border_box(x: u16, y: u16, width: u16, height: u16, fg: Color, bg: Color) {
-    
-    let mut stdout = stdout();
+fn create_border_box(
+    buffer: &mut Buffer,
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+    fg: Color,
+    bg: Color
+) {
+
+    // A border needs at least two chars in each direction
+    if width < 2 || height < 2 { return; }
 
     // Create the top of the box
-    execute!(
-        stdout,
-        cursor::MoveTo(x, y),
-        SetForegroundColor(fg),
-        SetBackgroundColor(bg),
-        Print(chars::TOP_LEFT),
-        Print(chars::HORIZONTAL.repeat((width - 2) as usize)),
-        Print(chars::TOP_RIGHT),
-        ResetColor
-    ).unwrap();
+    buffer.set_string(
+        x,
+        y,
+        &format!(
+            "{}{}{}",
+            chars::TOP_LEFT,
+            chars::HORIZONTAL.repeat((width - 2) as usize),
+            chars::TOP_RIGHT
+        ),
+        fg,
+        bg
+    );
 
     // Create all the sides
     for i in 0..(height - 2) {
-        execute!(
-            stdout,
-            cursor::MoveTo(x, y + (i + 1)),
-            SetForegroundColor(fg),
-            SetBackgroundColor(bg),
-            Print(chars::VERTICAL),
-            Print(chars::EMPTY.repeat((width - 2) as usize)),
-            Print(chars::VERTICAL),
-            ResetColor
-        ).unwrap();
+        buffer.set_string(
+            x,
+            y + (i + 1),
+            &format!(
+                "{}{}{}",
+                chars::VERTICAL,
+                chars::EMPTY.repeat((width - 2) as usize),
+                chars::VERTICAL
+            ),
+            fg,
+            bg
+        );
     }
 
     // Create the bottom of the box
-    execute!(
-        stdout,
-        cursor::MoveTo(x, y + height - 1),
-        SetForegroundColor(fg),
-        SetBackgroundColor(bg),
-        Print(chars::BOTTOM_LEFT),
-        Print(chars::HORIZONTAL.repeat((width - 2) as usize)),
-        Print(chars::BOTTOM_RIGHT),
-        ResetColor
-    ).unwrap();
+    buffer.set_string(
+        x,
+        y + height - 1,
+        &format!(
+            "{}{}{}",
+            chars::BOTTOM_LEFT,
+            chars::HORIZONTAL.repeat((width - 2) as usize),
+            chars::BOTTOM_RIGHT
+        ),
+        fg,
+        bg
+    );
 }
 
 /// A function that creates a filled, borderless box
-fn create_fill_box(x: u16, y: u16, width: u16, height: u16, bg: Color) {
+fn create_fill_box(buffer: &mut Buffer, x: u16, y: u16, width: u16, height: u16, bg: Color) {
 
-    let mut stdout = stdout();
+    // Simply write the color to each cell
+    buffer.fill(x, y, width, height, &Cell { bg, ..Cell::default() });
+}
 
-    // Simply write the color to each row
-    for h in 0..height {
-        execute!(
-            stdout,
-            cursor::MoveTo(x, y + h),
-            SetBackgroundColor(bg),
-            Print(chars::EMPTY.repeat(width as usize))
-        ).unwrap();
+/// Return the text shown in the middle of a progress widget at `value`: `label`
+/// if there is one, otherwise the percentage if `percentage` is set
+fn progress_label(label: &Option<String>, percentage: bool, value: f64) -> Option<String> {
+    match label {
+        Some(label) => Some(label.clone()),
+        None if percentage => Some(format!("{}%", (value * 100.0).round())),
+        None => None,
     }
 }
 
-/// A simple label widget for displaying text.
+/// Return the first grapheme of `text` to show in a space `width` cells wide,
+/// moving on from `scroll` only as far as needed to keep the cursor at grapheme
+/// `cursor` visible
+fn scroll_to(text: &str, scroll: usize, cursor: usize, width: usize) -> usize {
+    if cursor <= scroll {
+        return cursor;
+    }
+    let widths: Vec<usize> = text::graphemes(text).map(text::grapheme_width).collect();
+    let mut first = scroll;
+    let mut used = widths[first..cursor].iter().sum::<usize>()
+        + widths.get(cursor).copied().unwrap_or(1).max(1);
+    while used > width && first < cursor {
+        used -= widths[first];
+        first += 1;
+    }
+    first
+}
+
+/// A clickable button with a text caption. Buttons are activated by clicking on
+/// them, or by pressing Enter or Space while they have the focus.
 /// 
 /// Example:
 /// 
@@ -82,87 +131,97 @@ fn create_fill_box(x: u16, y: u16, width: u16, height: u16, bg: Color) {
 /// fn main() {
 /// 
 ///     // Create the window
-///     let mut window = Window::new();
-///
-///     // Create the label
-///     let mut label = Label::builder()
-///         .text(String::from("This is text right here."))
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create a button that quits the window
+///     let button = Button::builder()
+///         .text(String::from("Quit"))
+///         .on_click(|ctx| ctx.quit())
 ///         .build();
-///     window.grid(Box::new(&mut label), 1, 1, 1, 1);
+///     window.grid(Box::new(button), 1, 1, 1, 1);
 ///     // window.run();
-///     # window.quit();
 /// }
 /// ```
-pub struct Label {
-    /// A tuple containg two [`bool`]s; whether there is a border, and whether to
-    /// show the border
-    border_: (bool, bool),
-    /// The stdout to which all the widgets are printed (not very effective at the
-    /// moment; there's no guarantee that all widgets will be printed to this stdout)
-    stdout: std::io::Stdout,
-    /// The text that the label contains
+pub struct Button {
+    /// The area the button was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// The callbacks run when the button is activated, or a key is pressed
+    callbacks: Callbacks,
+    /// The number of grid columns this button spans
+    colspan: u16,
+    /// Whether the button has the focus
+    focused: bool,
+    /// Whether the mouse pointer is over the button
+    hovered: bool,
+    /// Whether the button is being pressed with the mouse
+    pressed: bool,
+    /// The number of grid rows this button spans
+    rowspan: u16,
+    /// The caption shown on the button
     text_: String,
-    /// The [`Theme`] that this label uses for it's colors
+    /// The [`Theme`] that this button uses for its colors
     theme_: Theme,
-    /// The width of the label, in chars
-    width: u16,
     /// The x position of this child, in either characters or grid units
     x: u16,
     /// The y position of this child, in either characters or grid units
     y: u16,
 }
-impl Label {
+impl Button {
     // The builder functions. These can be used to optionally customize options.
     // Be sure to call [`build()`] to finalize the creation.
 
-    /// Sets the border configuration [`bool`]s to `border`. `border` is tuple
-    /// containg two [`bool`]s; whether there is a border, and whether to show
-    /// the border. Use when building the label.
+    /// Adds a callback that is run when the button is activated: when it is
+    /// clicked, or when Enter or Space is pressed while it has the focus. Use when
+    /// building the button.
     /// 
     /// For example:
     /// 
     /// ```
     /// # use rusty_panther::prelude::*;
     /// # use rusty_panther::widgets::*;
-    /// #
+    /// # 
     /// # fn main() {
-    /// let label = Label::builder()
-    ///     .border((true, false))
+    /// let button = Button::builder()
+    ///     .on_click(|ctx| ctx.quit())
     ///     .build();
     /// # }
     /// ```
-    pub fn border(mut self, border: (bool, bool)) -> Label {
-        self.border_ = border;
+    pub fn on_click(mut self, callback: impl FnMut(&mut Context) + 'static) -> Button {
+        self.callbacks.on_click(callback);
         self
     }
 
-    // TODO
-    // /// Sets the stdout to `stdout`.
-    // pub fn set_stdout(mut self, stdout: RawTerminal<std::io::Stdout>) -> Label {
-    //     self.stdout = stdout;
-    //     self
-    // }
-
-    /// Sets the label's text to `text`, a [`String`]. Use when building the label.
+    /// Adds a callback that is run when `key` is pressed while the button has
+    /// the focus. Use when building the button.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
     /// 
     /// For example:
     /// 
     /// ```
     /// # use rusty_panther::prelude::*;
     /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     /// # 
     /// # fn main() {
-    /// let label = Label::builder()
-    ///     .text(String::from("This is text."))
+    /// let button = Button::builder()
+    ///     .on_key(KeyCode::Esc, |ctx| ctx.quit())
+    ///     .on_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL), |ctx| ctx.quit())
     ///     .build();
     /// # }
     /// ```
-    pub fn text(mut self, text: String) -> Label {
-        self.text_ = text;
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> Button {
+        self.callbacks.on_key(key, callback);
         self
     }
 
-    /// Sets the label's theme to `theme`, a [`Theme`]. Use when building the label.
+    /// Sets the button's theme to `theme`, a [`Theme`]. The theme's hover, focus
+    /// and pressed colors are used for the button's different states. Use when
+    /// building the button.
     /// 
     /// For example:
     /// 
@@ -171,17 +230,18 @@ impl Label {
     /// # use rusty_panther::widgets::*;
     /// # 
     /// # fn main() {
-    /// let label = Label::builder()
+    /// let button = Button::builder()
     ///     .set_theme(default_theme())
     ///     .build();
     /// # }
     /// ```
-    pub fn set_theme(mut self, theme: Theme) -> Label {
+    pub fn set_theme(mut self, theme: Theme) -> Button {
         self.theme_ = theme;
         self
     }
 
-    /// Sets the label's width to `width`, a [`u16`]. Use when building the label.
+    /// Sets the button's caption to `text`, a [`String`]. Use when building the
+    /// button.
     /// 
     /// For example:
     /// 
@@ -190,280 +250,6332 @@ impl Label {
     /// # use rusty_panther::widgets::*;
     /// # 
     /// # fn main() {
-    /// let label = Label::builder()
-    ///     .set_width(3)
+    /// let button = Button::builder()
+    ///     .text(String::from("OK"))
     ///     .build();
     /// # }
     /// ```
-    pub fn set_width(mut self, width: u16) -> Label {
-        self.width = width;
+    pub fn text(mut self, text: String) -> Button {
+        self.text_ = text;
         self
     }
 }
-impl Buildable for Label {
+impl Buildable for Button {
 
-    fn build(self) -> Label {
-        let len: u16 = (self.text_.len() as u16)+1;
-        Label {
-            border_: self.border_,
-            stdout: self.stdout,
+    fn build(self) -> Button {
+        Button {
+            area: self.area,
+            callbacks: self.callbacks,
+            colspan: self.colspan,
+            focused: self.focused,
+            hovered: self.hovered,
+            pressed: self.pressed,
+            rowspan: self.rowspan,
             text_: self.text_,
             theme_: self.theme_,
-            width: len,
             x: self.x,
             y: self.y,
         }
     }
 
-    fn builder() -> Label {
-        Label {
-            border_: (true, true),
-            stdout: stdout(),
+    fn builder() -> Button {
+        Button {
+            area: (0, 0, 0, 0),
+            callbacks: Callbacks::default(),
+            colspan: 1,
+            focused: false,
+            hovered: false,
+            pressed: false,
+            rowspan: 1,
             text_: String::from(""),
             theme_: default_theme(),
-            width: 10,
             x: 1,
             y: 1,
         }
     }
 
-    fn new() -> Label {
-        Label::builder().build()
+    fn new() -> Button {
+        Button::builder().build()
     }
 }
-impl Widget for Label {
+impl Eventable for Button {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        match event {
+            Event::Key(KeyEvent { code: KeyCode::Enter, .. })
+            | Event::Key(KeyEvent { code: KeyCode::Char(' '), .. }) => {
+                let activated = self.callbacks.click(ctx);
+                self.callbacks.handle(event, ctx) || activated
+            },
+            Event::Mouse(MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), .. }) => {
+                self.pressed = true;
+                self.callbacks.click(ctx);
+                true
+            },
+            Event::Mouse(MouseEvent { kind: MouseEventKind::Up(MouseButton::Left), .. }) => {
+                self.pressed = false;
+                true
+            },
+            _ => self.callbacks.handle(event, ctx),
+        }
+    }
+}
+impl Focusable for Button {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for Button {
 
-    fn draw(&mut self, x: u16, y: u16, width: u16, height: u16) {
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
 
-        // The positioning of the text
-        let mut text_x: u16 = x;
-        let mut text_y: u16 = y;
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { Some(self) }
 
-        // Create the background box, and if there needs to be a border, create
-        // the border.
-        if self.border_.0 {
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
 
-            // Make sure the text doesn't end up on the border
-            text_x += 1;
-            text_y += 1;
+        // A size of `0` means the button was placed with `add()`, so use its
+        // natural size
+        let width = if width == 0 { text::width(&self.text_) as u16 + 4 } else { width };
+        let height = if height == 0 { 3 } else { height };
+        self.area = (x, y, width, height);
 
-            // Create the bordered box
-            create_border_box(
-                x,
-                y,
-                width + 1,
-                height,
-                self.theme_.get_fg_rgb(),
-                self.theme_.get_bg_rgb()
-            );
+        // Pick the colors for the button's current state. Pressing takes priority
+        // over hovering, which takes priority over the focus.
+        let fg = if self.hovered {
+            self.theme_.get_hover_rgb()
+        }
+        else if self.focused {
+            self.theme_.get_focus_rgb()
         }
         else {
-            
-            // Create the unbordered box
-            create_fill_box(
-                x,
-                y,
-                width,
-                height,
-                self.theme_.get_bg_rgb()
-            );
+            self.theme_.get_fg_rgb()
+        };
+        let bg = if self.pressed {
+            self.theme_.get_pressed_rgb()
         }
+        else {
+            self.theme_.get_bg_rgb()
+        };
 
-        // Create the label's text
-        execute!(
-            self.stdout,
-            cursor::MoveTo(text_x, text_y),
-            SetForegroundColor(self.theme_.get_fg_rgb()),
-            Print(&self.text_),
-            ResetColor
-        ).unwrap();
+        // Create the bordered box, with the caption in the middle
+        create_border_box(buffer, x, y, width, height, fg, bg);
+        let caption = text::truncate(&self.text_, width.saturating_sub(2) as usize);
+        let caption_width = text::width(&caption) as u16;
+        buffer.set_string(
+            x + width.saturating_sub(caption_width) / 2,
+            y + height / 2,
+            &caption,
+            fg,
+            bg
+        );
+        Ok(())
     }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
     fn get_x(&self) -> u16 { self.x }
     fn get_y(&self) -> u16 { self.y }
+    fn hover_add(&mut self) { self.hovered = true; }
+    fn hover_remove(&mut self) {
+        self.hovered = false;
+        self.pressed = false;
+    }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
     fn set_x(&mut self, x: u16) { self.x = x; }
     fn set_y(&mut self, y: u16) { self.y = y; }
 }
 
-/// The main window for the terminal application; this contains all the widgets.
+/// A container widget that lays out its own children inside the area it is given,
+/// using its own [`Grid`]. Frames can be nested inside each other, and can have a
+/// border with a title.
 /// 
 /// Example:
 /// 
 /// ```
 /// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
 /// 
 /// fn main() {
-///     
-///     // The window
-///     let mut window = widgets::Window::new();
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create a label inside of a frame
+///     let label = Label::builder()
+///         .text(String::from("Inside"))
+///         .build();
+///     let mut frame = Frame::builder()
+///         .title(String::from("Panel"))
+///         .build();
+///     frame.grid(Box::new(label), 1, 1, 1, 1);
+///     window.grid(Box::new(frame), 1, 1, 2, 2);
 ///     // window.run();
-/// # window.quit()
 /// }
 /// ```
-pub struct Window<'a> {
-    /// All the immediate children of this widget (e.g., excludes grandchildren, 
-    /// great-grandchildren, etc.)
-    children: Vec<Box<&'a mut dyn Widget>>,
-    /// The [`Grid`] that manages all of the widget-sizing calculations
+pub struct Frame {
+    /// The children added with [`Parent::add()`], placed in characters relative
+    /// to the frame's top left corner
+    absolute_children: Children,
+    /// The area the frame was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the frame has a border
+    border_: bool,
+    /// The callbacks run when the user interacts with the frame, or when an
+    /// event its children didn't handle bubbles up to it
+    callbacks: Callbacks,
+    /// All the gridded children of this frame
+    children: Children,
+    /// The number of grid columns this frame spans
+    colspan: u16,
+    /// The [`Grid`] that manages the sizing of this frame's children
     grid: Grid,
-    /// The height of the terminal screen
-    screen_height: u16,
-    /// The current width of the terminal screen
-    screen_width: u16,
-    /// The stdout to which all the widgets are printed.
-    stdout: std::io::Stdout,
-    /// The [`Theme`] that the window uses.
+    /// The number of grid rows this frame spans
+    rowspan: u16,
+    /// The [`Theme`] that this frame uses for its colors
     theme_: Theme,
+    /// The title shown in the frame's border, if there is one
+    title_: Option<String>,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
 }
-impl<'a> Window<'a> {
-
-    /// Draws all the child widgets based on the terminal's width and height
-    pub fn draw_children(&mut self) {
-
-        // Clear the screen
-        execute!(self.stdout, Clear(ClearType::All)).unwrap();
-
-        // Update the grid's size
-        self.update_grid_size();
-
-        // For each child widget, calculate its positioning and size
-        for child in &mut self.children {
-            
-            // Get the placement and size of the child
-            let (x, y) = self.grid.get_placement_chars(child.get_x() as u8, child.get_y() as u8);
-            let width = self.grid.get_column_chars(child.get_x() as u8);
-            let height = self.grid.get_row_chars(child.get_y() as u8);
-            println!("x×y {}×{}", x, y);
-            println!("wxh {}x{}", size().unwrap().0, size().unwrap().1);
+impl Frame {
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
 
-            // Place the child
-            child.draw(x, y, width, height);
-        }
+    /// Sets whether the frame has a border. Use when building the frame.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// #
+    /// # fn main() {
+    /// let frame = Frame::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> Frame {
+        self.border_ = border;
+        self
     }
 
-    /// Quits the window and the alternate screen.
-    pub fn quit(&mut self) {
-        execute!(self.stdout, DisableMouseCapture).unwrap();
-        execute!(self.stdout, LeaveAlternateScreen).unwrap();
-        disable_raw_mode().unwrap();
+    /// Adds a callback that is run when the frame, or a child that doesn't handle
+    /// clicks itself, is clicked. Use when building the frame.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// #
+    /// # fn main() {
+    /// let frame = Frame::builder()
+    ///     .on_click(|ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_click(mut self, callback: impl FnMut(&mut Context) + 'static) -> Frame {
+        self.callbacks.on_click(callback);
+        self
     }
 
-    /// Run the application; this starts the event listener.
+    /// Adds a callback that is run when `key` is pressed while a child of the
+    /// frame has the focus, and the child doesn't handle the key itself. Use when
+    /// building the frame.
     /// 
-    /// More information on connection to events will appear here when implemented.
-    pub fn run(&mut self) {
-
-        // Start the event listener
-        loop {
-            match read().unwrap() {
-                Event::Key(
-                    KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::CONTROL }
-                ) => {
-                    self.quit();
-                    return;
-                },
-                Event::Key(_) => {},
-                Event::Mouse(event) => {
-                    // Left click
-                    if event.kind == MouseEventKind::Down(MouseButton::Left) {
-                    }
-                }
-                Event::Resize(width, height) => {
-                    self.screen_height = height;
-                    self.screen_width = width;
-                    self.draw_children();
-                }
-            }
-        }
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// #
+    /// # fn main() {
+    /// let frame = Frame::builder()
+    ///     .on_key(KeyCode::Esc, |ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> Frame {
+        self.callbacks.on_key(key, callback);
+        self
     }
 
-    /// Updates the grid size based on the terminal size.
-    fn update_grid_size(&mut self) {
-        let (width, height) = size().expect("size()");
-        self.grid.set_height_chars(height);
-        self.grid.set_width_chars(width);
+    /// Sets the [`Grid`] that the frame lays its children out with. Use when
+    /// building the frame.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// #
+    /// # fn main() {
+    /// let frame = Frame::builder()
+    ///     .set_grid(Grid::builder().width(2).height(3).build())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_grid(mut self, grid: Grid) -> Frame {
+        self.grid = grid;
+        self
     }
-    
-    // The builder functions. These can be used to optionally customize options.
-    // Be sure to call [`build()`] to finalize the creation.
 
-    // TODO
-    // /// Set the stdout for this window to `stdout`.
-    // pub fn set_stdout(mut self, stdout: RawTerminal<std::io::Stdout>) -> Window {
-    //     self.stdout = stdout;
-    //     self
-    // }
-
-    /// Set the theme for the window. Use when building the window.
+    /// Sets the frame's theme to `theme`, a [`Theme`]. Use when building the frame.
     /// 
     /// For example:
     /// 
     /// ```
     /// # use rusty_panther::prelude::*;
     /// # use rusty_panther::widgets::*;
-    /// # 
+    /// #
     /// # fn main() {
-    /// let mut window = Window::builder()
-    ///     .theme(default_theme())
+    /// let frame = Frame::builder()
+    ///     .set_theme(default_theme())
     ///     .build();
-    /// # window.quit();
     /// # }
     /// ```
-    pub fn theme(mut self, theme: Theme) -> Window<'a> {
+    pub fn set_theme(mut self, theme: Theme) -> Frame {
         self.theme_ = theme;
         self
     }
+
+    /// Sets the title shown in the frame's border. Only shown if the frame has
+    /// a border. Use when building the frame.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// #
+    /// # fn main() {
+    /// let frame = Frame::builder()
+    ///     .title(String::from("Settings"))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn title(mut self, title: String) -> Frame {
+        self.title_ = Some(title);
+        self
+    }
 }
-impl<'a> Buildable for Window<'a> {
+impl Buildable for Frame {
 
-    fn build(self) -> Window<'a> {
-        Window {
+    fn build(self) -> Frame {
+        Frame {
+            absolute_children: self.absolute_children,
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
             children: self.children,
+            colspan: self.colspan,
             grid: self.grid,
-            screen_height: self.screen_height,
-            screen_width: self.screen_width,
-            stdout: self.stdout,
-            theme_: self.theme_
+            rowspan: self.rowspan,
+            theme_: self.theme_,
+            title_: self.title_,
+            x: self.x,
+            y: self.y,
         }
     }
 
-    fn builder() -> Window<'a> {
-        enable_raw_mode().unwrap();
-        execute!(stdout(), EnterAlternateScreen).unwrap();
-        execute!(stdout(), EnableMouseCapture).unwrap();
-        Window {
+    fn builder() -> Frame {
+        Frame {
+            absolute_children: Vec::new(),
+            area: (0, 0, 0, 0),
+            border_: true,
+            callbacks: Callbacks::default(),
             children: Vec::new(),
+            colspan: 1,
             grid: Grid::new(),
-            screen_height: size().expect("screen size").1,
-            screen_width: size().expect("screen size").0,
-            stdout: stdout(),
-            theme_: default_theme()
+            rowspan: 1,
+            theme_: default_theme(),
+            title_: None,
+            x: 1,
+            y: 1,
         }
     }
 
-    fn new() -> Window<'a> {
-        Window::builder().build()
+    fn new() -> Frame {
+        Frame::builder().build()
     }
 }
-impl<'a> Parent<'a> for Window<'a> {
-    fn add(&mut self, child: Box<&'a mut dyn Widget>, x: u16, y: u16) {
-        self.children.push(child);
-        self.children.last_mut().unwrap().draw(x, y, 0, 0);
-        execute!(self.stdout, cursor::MoveTo(1, 1)).unwrap();
-        self.stdout.flush().unwrap();
+impl Parent for Frame {
+    fn add_with_id(&mut self, id: WidgetId, child: Box<dyn Widget>, x: u16, y: u16) {
+        add_child(&mut self.absolute_children, id, child, x, y);
     }
 
-    fn grid(&mut self, child: Box<&'a mut dyn Widget>,
+    fn grid_with_id(&mut self,
+        id: WidgetId,
+        child: Box<dyn Widget>,
         row: u16,
         col: u16,
-        _rowspan: u16,
-        _colspan: u16) {
+        rowspan: u16,
+        colspan: u16) {
+        grid_child(&mut self.children, id, child, row, col, rowspan, colspan);
+    }
 
-        // Set this new child's row and column
-        child.set_x(col);
-        child.set_y(row);
-        self.children.push(child);
-        
-        // Redraw the children
-        self.draw_children();
+    fn remove(&mut self, id: WidgetId) -> Option<Box<dyn Widget>> {
+        remove_child([&mut self.children, &mut self.absolute_children], id)
+    }
+}
+impl Eventable for Frame {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        self.callbacks.handle(event, ctx)
+    }
+}
+impl Widget for Frame {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_parent(&mut self) -> Option<&mut dyn Parent> { Some(self) }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        self.children.iter_mut()
+            .chain(self.absolute_children.iter_mut())
+            .map(|(_, child)| &mut **child as &mut dyn Widget)
+            .collect()
+    }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+        self.area = (x, y, width, height);
+
+        // Create the background box, and if there needs to be a border, create
+        // the border and its title.
+        if self.border_ {
+            create_border_box(
+                buffer,
+                x,
+                y,
+                width,
+                height,
+                self.theme_.get_fg_rgb(),
+                self.theme_.get_bg_rgb()
+            );
+            if let Some(title) = &self.title_ {
+                let room = width.saturating_sub(4) as usize;
+                buffer.set_string(
+                    x + 2,
+                    y,
+                    &text::truncate(&format!(" {} ", title), room),
+                    self.theme_.get_fg_rgb(),
+                    self.theme_.get_bg_rgb()
+                );
+            }
+
+            // The grid keeps a one-char margin on each side, which lines up with
+            // the border. Children fill the gap to their right, so take one more
+            // char away to keep them off the right-hand border.
+            self.grid.set_width_chars(width.saturating_sub(1));
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, self.theme_.get_bg_rgb());
+            self.grid.set_width_chars(width);
+        }
+        self.grid.set_height_chars(height);
+
+        // Draw the gridded children, relative to the frame's top left corner
+        draw_grid(&mut self.children, &self.grid, buffer, (x, y, width, height))?;
+
+        // Draw the children that were placed in characters at their natural size
+        for (_, child) in &mut self.absolute_children {
+            let (child_x, child_y) = (child.get_x(), child.get_y());
+            child.draw(buffer, x + child_x, y + child_y, 0, 0)?;
+        }
+        Ok(())
+    }
+
+    fn find(&self, id: WidgetId) -> Option<&(dyn Widget + 'static)> {
+        find_child([&self.children, &self.absolute_children], id)
+    }
+
+    fn find_mut(&mut self, id: WidgetId) -> Option<&mut (dyn Widget + 'static)> {
+        find_child_mut([&mut self.children, &mut self.absolute_children], id)
+    }
+
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A function that sends `event` down the chain of children given by `path`,
+/// starting at `widget`. The last widget in the chain gets the event first; if
+/// it doesn't handle it, the event bubbles back up to its parents. Returns
+/// whether any widget handled the event.
+fn dispatch_event(
+    widget: &mut dyn Widget,
+    path: &[usize],
+    event: Event,
+    ctx: &mut Context
+) -> bool {
+
+    // Give the event to the child on the path first
+    if let Some((first, rest)) = path.split_first() {
+        if let Some(child) = widget.children_mut().into_iter().nth(*first) {
+            if dispatch_event(child, rest, event, ctx) {
+                return true;
+            }
+        }
+    }
+
+    // The child didn't handle the event, so this widget gets a try
+    let handled = match widget.as_eventable() {
+        Some(eventable) => eventable.event_send(event, ctx),
+        None => false,
+    };
+    ctx.handled_at(path.len());
+    handled
+}
+
+/// A function that returns the path of child indices leading from `children` to
+/// the innermost widget at (`x`, `y`). Widgets drawn last are on top, so they
+/// are checked first.
+fn path_at(children: Vec<&mut dyn Widget>, x: u16, y: u16) -> Vec<usize> {
+    for (i, child) in children.into_iter().enumerate().rev() {
+        if area_contains(child.get_area(), x, y) {
+            let mut path = vec![i];
+            path.extend(path_at(child.children_mut(), x, y));
+            return path;
+        }
+    }
+    Vec::new()
+}
+
+/// A function that adds the paths of all the focusable widgets among `children`
+/// and their descendants to `chain`, in the order they are drawn. `path` is the
+/// path leading to `children`.
+fn focus_chain(
+    children: Vec<&mut dyn Widget>,
+    path: &mut Vec<usize>,
+    chain: &mut Vec<Vec<usize>>
+) {
+    for (i, child) in children.into_iter().enumerate() {
+        path.push(i);
+        if child.as_focusable().is_some() {
+            chain.push(path.clone());
+        }
+        focus_chain(child.children_mut(), path, chain);
+        path.pop();
+    }
+}
+
+/// A function that takes the focus and the hover highlight away from all of
+/// `children` and their descendants, e.g. because they are being hidden
+fn hide(children: Vec<&mut dyn Widget>) {
+    for child in children {
+        if let Some(focusable) = child.as_focusable() {
+            focusable.focus_remove();
+        }
+        child.hover_remove();
+        hide(child.children_mut());
+    }
+}
+
+/// A function that gives a tick to all of `children` and their descendants.
+/// Returns whether any of them changed.
+fn tick_all(children: Vec<&mut dyn Widget>, ctx: &mut Context) -> bool {
+    let mut changed = false;
+    for child in children {
+        if let Some(eventable) = child.as_eventable() {
+            changed |= eventable.tick(ctx);
+        }
+        changed |= tick_all(child.children_mut(), ctx);
+    }
+    changed
+}
+
+/// A function that draws again, into `buffer`, all of `children` and their
+/// descendants that changed since they were last drawn. Returns whether any of
+/// them was drawn, and whether any of them changed its area, as a tuple.
+fn draw_dirty(children: Vec<&mut dyn Widget>, buffer: &mut Buffer) -> Result<(bool, bool)> {
+    let (mut drawn, mut moved) = (false, false);
+    for child in children {
+        let area = child.get_area();
+        if child.draw_dirty(buffer)? {
+            drawn = true;
+            moved |= child.get_area() != area;
+        }
+        let (children_drawn, children_moved) = draw_dirty(child.children_mut(), buffer)?;
+        drawn |= children_drawn;
+        moved |= children_moved;
+    }
+    Ok((drawn, moved))
+}
+
+/// A function that draws each of the gridded `children` into its cells of `grid`,
+/// relative to the top left corner of `area` and cut off at its edges. Children
+/// whose cells are empty, because the grid is too small to hold them, aren't
+/// drawn at all, since an empty size would mean their natural size. Rows and
+/// columns that don't fit in a [`u8`] are errors, like any other the grid lacks.
+fn draw_grid(
+    children: &mut Children,
+    grid: &Grid,
+    buffer: &mut Buffer,
+    area: (u16, u16, u16, u16)
+) -> Result<()> {
+    let (x, y, width, height) = area;
+    for (_, child) in children {
+        let (rowspan, colspan) = child.get_span();
+        let column = u8::try_from(child.get_x())
+            .map_err(|_| Error::NoSuchColumn(child.get_x() as usize))?;
+        let row = u8::try_from(child.get_y())
+            .map_err(|_| Error::NoSuchRow(child.get_y() as usize))?;
+        let (child_x, child_y, child_width, child_height) = grid.get_area_chars(
+            column,
+            row,
+            u8::try_from(colspan).unwrap_or(u8::MAX),
+            u8::try_from(rowspan).unwrap_or(u8::MAX)
+        )?;
+        let child_width = child_width.min(width.saturating_sub(child_x));
+        let child_height = child_height.min(height.saturating_sub(child_y));
+        if child_width == 0 || child_height == 0 {
+            skip_draw(vec![&mut **child]);
+            continue;
+        }
+        child.draw(
+            buffer,
+            x.saturating_add(child_x),
+            y.saturating_add(child_y),
+            child_width,
+            child_height
+        )?;
+    }
+    Ok(())
+}
+
+/// A function that tells all of `children` and their descendants that they
+/// weren't drawn, because their parent had no room for them
+fn skip_draw(children: Vec<&mut dyn Widget>) {
+    for child in children {
+        child.skip_draw();
+        skip_draw(child.children_mut());
+    }
+}
+
+/// A function that returns the widget that the chain of child indices `path`
+/// leads to, starting at `children`
+fn widget_at_path<'w>(
+    children: Vec<&'w mut dyn Widget>,
+    path: &[usize]
+) -> Option<&'w mut dyn Widget> {
+    let (first, rest) = path.split_first()?;
+    let child = children.into_iter().nth(*first)?;
+    if rest.is_empty() {
+        Some(child)
+    }
+    else {
+        widget_at_path(child.children_mut(), rest)
+    }
+}
+
+/// A function that returns whether (`x`, `y`) is inside `area`, an `(x, y,
+/// width, height)` tuple
+fn area_contains(area: (u16, u16, u16, u16), x: u16, y: u16) -> bool {
+    let (area_x, area_y, width, height) = area;
+    x >= area_x
+        && y >= area_y
+        && x < area_x.saturating_add(width)
+        && y < area_y.saturating_add(height)
+}
+
+/// The children of a parent widget, each with its [`WidgetId`], in the order
+/// they are drawn
+type Children = Vec<(WidgetId, Box<dyn Widget>)>;
+
+/// A function that places `child` at (`x`, `y`) and adds it to `children` with
+/// the ID `id`
+fn add_child(children: &mut Children, id: WidgetId, mut child: Box<dyn Widget>, x: u16, y: u16) {
+    child.set_x(x);
+    child.set_y(y);
+    children.push((id, child));
+}
+
+/// A function that returns the address of `widget`, for telling apart widgets
+/// that are moved around the tree
+fn address(widget: &dyn Widget) -> *const () {
+    std::ptr::from_ref(widget).cast()
+}
+
+/// A function that returns the widget with the ID `id` among the lists of
+/// children `lists` and their descendants
+fn find_child<'w>(lists: [&'w Children; 2], id: WidgetId) -> Option<&'w (dyn Widget + 'static)> {
+    for (child_id, child) in lists.into_iter().flatten() {
+        if *child_id == id {
+            return Some(&**child);
+        }
+        if let Some(found) = child.find(id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// A function that returns the widget with the ID `id` among the lists of
+/// children `lists` and their descendants mutably
+fn find_child_mut<'w>(
+    lists: [&'w mut Children; 2],
+    id: WidgetId
+) -> Option<&'w mut (dyn Widget + 'static)> {
+    for (child_id, child) in lists.into_iter().flatten() {
+        if *child_id == id {
+            return Some(&mut **child);
+        }
+        if let Some(found) = child.find_mut(id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// A function that sets the row `row`, column `col` and span of `child`, and
+/// adds it to `children` with the ID `id`
+fn grid_child(
+    children: &mut Children,
+    id: WidgetId,
+    mut child: Box<dyn Widget>,
+    row: u16,
+    col: u16,
+    rowspan: u16,
+    colspan: u16
+) {
+    child.set_x(col);
+    child.set_y(row);
+    child.set_span(rowspan, colspan);
+    children.push((id, child));
+}
+
+/// A function that returns the path of child indices leading from `children` to
+/// the widget at the address `target`, if it is among them or their descendants
+fn path_to(children: Vec<&mut dyn Widget>, target: *const ()) -> Option<Vec<usize>> {
+    for (i, child) in children.into_iter().enumerate() {
+        if address(child) == target {
+            return Some(vec![i]);
+        }
+        if let Some(mut path) = path_to(child.children_mut(), target) {
+            path.insert(0, i);
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// A function that removes the widget with the ID `id` from the lists of
+/// children `lists`, or from their descendants, and returns it
+fn remove_child(lists: [&mut Children; 2], id: WidgetId) -> Option<Box<dyn Widget>> {
+    for children in lists {
+        if let Some(i) = children.iter().position(|(child_id, _)| *child_id == id) {
+            return Some(children.remove(i).1);
+        }
+        for (_, child) in children.iter_mut() {
+            if let Some(removed) = child.as_parent().and_then(|parent| parent.remove(id)) {
+                return Some(removed);
+            }
+        }
+    }
+    None
+}
+
+/// A vertical gauge that fills up from the bottom as a value goes from `0.0` to
+/// `1.0`. Like a [`ProgressBar`], it uses eighth-block characters so that the
+/// level moves smoothly, and can show a percentage or a label in the middle.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create a gauge that is a third full
+///     let gauge = Gauge::builder()
+///         .value(0.33)
+///         .percentage(true)
+///         .build();
+///     window.grid(Box::new(gauge), 1, 1, 3, 1);
+///     // window.run();
+/// }
+/// ```
+pub struct Gauge {
+    /// The area the gauge was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the gauge has a border
+    border_: bool,
+    /// The number of grid columns this gauge spans
+    colspan: u16,
+    /// The text shown in the middle of the gauge, if there is one
+    label_: Option<String>,
+    /// Whether the percentage is shown in the middle of the gauge, when there is
+    /// no label
+    percentage_: bool,
+    /// The number of grid rows this gauge spans
+    rowspan: u16,
+    /// The [`Theme`] that this gauge uses for its colors
+    theme_: Theme,
+    /// How full the gauge is, from `0.0` to `1.0`
+    value_: f64,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl Gauge {
+
+    /// Return how full the gauge is, from `0.0` to `1.0`
+    pub fn get_value(&self) -> f64 {
+        self.value_
+    }
+
+    /// Set how full the gauge is to `value`, clamped between `0.0` and `1.0`
+    pub fn set_value(&mut self, value: f64) {
+        self.value_ = value.clamp(0.0, 1.0);
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the gauge has a border. Use when building the gauge.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let gauge = Gauge::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> Gauge {
+        self.border_ = border;
+        self
+    }
+
+    /// Sets the text shown in the middle of the gauge to `label`, a [`String`],
+    /// instead of the percentage. Use when building the gauge.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let gauge = Gauge::builder()
+    ///     .label(String::from("CPU"))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn label(mut self, label: String) -> Gauge {
+        self.label_ = Some(label);
+        self
+    }
+
+    /// Sets whether the percentage is shown in the middle of the gauge. Use when
+    /// building the gauge.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let gauge = Gauge::builder()
+    ///     .percentage(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn percentage(mut self, percentage: bool) -> Gauge {
+        self.percentage_ = percentage;
+        self
+    }
+
+    /// Sets the gauge's theme to `theme`, a [`Theme`]. The level is drawn in the
+    /// theme's foreground color. Use when building the gauge.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let gauge = Gauge::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> Gauge {
+        self.theme_ = theme;
+        self
+    }
+
+    /// Sets how full the gauge is at first to `value`, from `0.0` to `1.0`. Use
+    /// when building the gauge.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let gauge = Gauge::builder()
+    ///     .value(0.5)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn value(mut self, value: f64) -> Gauge {
+        self.set_value(value);
+        self
+    }
+}
+impl Buildable for Gauge {
+
+    fn build(self) -> Gauge {
+        Gauge {
+            area: self.area,
+            border_: self.border_,
+            colspan: self.colspan,
+            label_: self.label_,
+            percentage_: self.percentage_,
+            rowspan: self.rowspan,
+            theme_: self.theme_,
+            value_: self.value_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> Gauge {
+        Gauge {
+            area: (0, 0, 0, 0),
+            border_: true,
+            colspan: 1,
+            label_: None,
+            percentage_: false,
+            rowspan: 1,
+            theme_: default_theme(),
+            value_: 0.0,
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> Gauge {
+        Gauge::builder().build()
+    }
+}
+impl Widget for Gauge {
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+
+        // A size of `0` means the gauge was placed with `add()`, so use its
+        // natural size
+        let border = if self.border_ { 1 } else { 0 };
+        let width = if width == 0 { 4 + 2 * border } else { width };
+        let height = if height == 0 { 8 + 2 * border } else { height };
+        self.area = (x, y, width, height);
+
+        // Create the background box, and the border if there is one
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+        if self.border_ {
+            create_border_box(buffer, x, y, width, height, fg, bg);
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, bg);
+        }
+
+        // Fill the gauge from the bottom up, in eighths of a row. The full rows
+        // are filled with the foreground color, and the partly filled row on top
+        // of them uses a block character.
+        let inner_x = x + border;
+        let inner_width = width.saturating_sub(2 * border);
+        let inner_height = height.saturating_sub(2 * border);
+        let bottom = y + border + inner_height;
+        let eighths = (self.value_ * inner_height as f64 * 8.0).round() as u16;
+        let (full, partial) = (eighths / 8, eighths % 8);
+        create_fill_box(buffer, inner_x, bottom - full, inner_width, full, fg);
+        if partial > 0 && full < inner_height {
+            let block = chars::BLOCKS_VERTICAL[partial as usize - 1].repeat(inner_width as usize);
+            buffer.set_string(inner_x, bottom - full - 1, &block, fg, bg);
+        }
+
+        // Create the label in the middle, inverting its colors where the gauge is
+        // filled so that it stays readable
+        if let Some(label) = progress_label(&self.label_, self.percentage_, self.value_) {
+            let label = text::truncate(&label, inner_width as usize);
+            let label_width = text::width(&label) as u16;
+            let label_x = inner_x + (inner_width - label_width) / 2;
+            let label_y = y + border + inner_height.saturating_sub(1) / 2;
+            if label_y >= bottom - full {
+                buffer.set_string(label_x, label_y, &label, bg, fg);
+            }
+            else {
+                buffer.set_string(label_x, label_y, &label, fg, bg);
+            }
+        }
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A simple label widget for displaying text.
+/// 
+/// The text can be wrapped between words or chars, aligned horizontally and
+/// vertically, and cut off with an ellipsis if it still doesn't fit; see
+/// [`Label::wrap()`], [`Label::align()`], [`Label::vertical_align()`] and
+/// [`Label::overflow()`].
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::new();
+///
+///     // Create the label
+///     let label = Label::builder()
+///         .text(String::from("This is text right here."))
+///         .build();
+///     window.grid(Box::new(label), 1, 1, 1, 1);
+///     // window.run();
+///     # window.quit();
+/// }
+/// ```
+pub struct Label {
+    /// How the lines of text are aligned horizontally
+    align_: Align,
+    /// The area the label was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// A tuple containg two [`bool`]s; whether there is a border, and whether to
+    /// show the border
+    border_: (bool, bool),
+    /// The callbacks run when the user interacts with the label
+    callbacks: Callbacks,
+    /// The number of grid columns this label spans
+    colspan: u16,
+    /// Whether the label changed since it was last drawn
+    dirty: bool,
+    /// Whether the label can take the focus
+    focusable_: bool,
+    /// Whether the label has the focus
+    focused: bool,
+    /// What happens to text that doesn't fit into the label
+    overflow_: Overflow,
+    /// The arguments the label was last drawn with, as an `(x, y, width, height)`
+    /// tuple, or [`None`] if it isn't laid out, because it wasn't drawn yet or
+    /// there was no room for it the last time
+    placement: Option<(u16, u16, u16, u16)>,
+    /// The number of grid rows this label spans
+    rowspan: u16,
+    /// The text that the label contains
+    text_: String,
+    /// The [`Theme`] that this label uses for it's colors
+    theme_: Theme,
+    /// How the lines of text are aligned vertically
+    vertical_align_: VerticalAlign,
+    /// The width of the label, in chars, if it was set; otherwise the label is as
+    /// wide as its text
+    width_: Option<u16>,
+    /// How the text is broken up into lines
+    wrap_: Wrap,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl Label {
+
+    /// Return the label's text
+    pub fn get_text(&self) -> &str {
+        &self.text_
+    }
+
+    /// Change the label's text to `text`. Only the label is drawn again, the next
+    /// time the window updates.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use std::time::Instant;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// let status = window.grid(Box::new(Label::new()), 1, 1, 1, 1);
+    /// 
+    /// window.get_mut::<Label>(status).unwrap().update_text(String::from("Ready"));
+    /// window.update(Instant::now());
+    /// assert!(window.screen().find("Ready").is_some());
+    /// # }
+    /// ```
+    pub fn update_text(&mut self, text: String) {
+        self.text_ = text;
+        self.dirty = true;
+    }
+
+    /// Change the label's theme to `theme`. Only the label is drawn again, the
+    /// next time the window updates.
+    pub fn update_theme(&mut self, theme: Theme) {
+        self.theme_ = theme;
+        self.dirty = true;
+    }
+
+    /// Change the label's width to `width` chars, or back to the width of its
+    /// text if `width` is [`None`]. This only affects labels placed with `add()`,
+    /// since gridded labels fill their grid cells. Only the label is drawn again,
+    /// the next time the window updates, unless its size changed.
+    pub fn update_width(&mut self, width: Option<u16>) {
+        self.width_ = width;
+        self.dirty = true;
+    }
+
+    /// Return the height of the label when it is placed with `add()` and is
+    /// `width` chars wide: enough for all of its lines of text
+    fn natural_height(&self, width: u16) -> u16 {
+        let room = if self.border_.0 { width.saturating_sub(1) } else { width };
+        let lines = self.wrap_.lines(&self.text_, room as usize).len().max(1) as u16;
+        if self.border_.0 { lines + 2 } else { lines }
+    }
+
+    /// Return the width of the label when it is placed with `add()`, in chars:
+    /// the set width, or otherwise enough for its longest line of text
+    fn natural_width(&self) -> u16 {
+        let longest = self.text_.lines().map(text::width).max().unwrap_or(0);
+        self.width_.unwrap_or(longest as u16 + 1)
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets how the lines of text are aligned horizontally inside the label. Use
+    /// when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// #
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .text(String::from("Centered"))
+    ///     .align(Align::Center)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn align(mut self, align: Align) -> Label {
+        self.align_ = align;
+        self
+    }
+
+    /// Sets the border configuration [`bool`]s to `border`. `border` is tuple
+    /// containg two [`bool`]s; whether there is a border, and whether to show
+    /// the border. Use when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// #
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .border((true, false))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: (bool, bool)) -> Label {
+        self.border_ = border;
+        self
+    }
+
+    /// Sets whether the label can take the focus. A focused label's border is
+    /// drawn in the [`Theme`]'s focus color. Use when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// #
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .focusable(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn focusable(mut self, focusable: bool) -> Label {
+        self.focusable_ = focusable;
+        self
+    }
+
+    /// Adds a callback that is run when the label is clicked. Use when building the
+    /// label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .on_click(|ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_click(mut self, callback: impl FnMut(&mut Context) + 'static) -> Label {
+        self.callbacks.on_click(callback);
+        self
+    }
+
+    /// Adds a callback that is run when `key` is pressed while the label has the
+    /// focus. Use when building the label.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .focusable(true)
+    ///     .on_key(KeyCode::Enter, |ctx| ctx.redraw())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> Label {
+        self.callbacks.on_key(key, callback);
+        self
+    }
+
+    /// Sets what happens to text that doesn't fit into the label, even after
+    /// wrapping: it is clipped by default. Use when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .overflow(Overflow::Ellipsis)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn overflow(mut self, overflow: Overflow) -> Label {
+        self.overflow_ = overflow;
+        self
+    }
+
+    /// Sets the label's theme to `theme`, a [`Theme`]. Use when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> Label {
+        self.theme_ = theme;
+        self
+    }
+
+    /// Sets the label's width to `width`, a [`u16`], instead of the width of its
+    /// text. Use when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .set_width(3)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_width(mut self, width: u16) -> Label {
+        self.width_ = Some(width);
+        self
+    }
+
+    /// Sets the label's text to `text`, a [`String`]. Use when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .text(String::from("This is text."))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn text(mut self, text: String) -> Label {
+        self.text_ = text;
+        self
+    }
+
+    /// Sets how the lines of text are aligned vertically inside the label. Use
+    /// when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .vertical_align(VerticalAlign::Middle)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn vertical_align(mut self, vertical_align: VerticalAlign) -> Label {
+        self.vertical_align_ = vertical_align;
+        self
+    }
+
+    /// Sets how the text is broken up into lines to fit into the label. By
+    /// default, lines are only broken at newlines. Use when building the label.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let label = Label::builder()
+    ///     .text(String::from("A long text, broken between its words"))
+    ///     .wrap(Wrap::Word)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn wrap(mut self, wrap: Wrap) -> Label {
+        self.wrap_ = wrap;
+        self
+    }
+}
+impl Buildable for Label {
+
+    fn build(self) -> Label {
+        Label {
+            align_: self.align_,
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
+            colspan: self.colspan,
+            dirty: self.dirty,
+            focusable_: self.focusable_,
+            focused: self.focused,
+            overflow_: self.overflow_,
+            placement: self.placement,
+            rowspan: self.rowspan,
+            text_: self.text_,
+            theme_: self.theme_,
+            vertical_align_: self.vertical_align_,
+            width_: self.width_,
+            wrap_: self.wrap_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> Label {
+        Label {
+            align_: Align::Left,
+            area: (0, 0, 0, 0),
+            border_: (true, true),
+            callbacks: Callbacks::default(),
+            colspan: 1,
+            dirty: false,
+            focusable_: false,
+            focused: false,
+            overflow_: Overflow::Clip,
+            placement: None,
+            rowspan: 1,
+            text_: String::from(""),
+            theme_: default_theme(),
+            vertical_align_: VerticalAlign::Top,
+            width_: None,
+            wrap_: Wrap::None,
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> Label {
+        Label::builder().build()
+    }
+}
+impl Eventable for Label {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        self.callbacks.handle(event, ctx)
+    }
+}
+impl Focusable for Label {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for Label {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> {
+        if self.focusable_ { Some(self) } else { None }
+    }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+        self.placement = Some((x, y, width, height));
+        self.dirty = false;
+
+        // A size of `0` means the label was placed with `add()`, so use its
+        // natural size
+        let width = if width == 0 { self.natural_width() } else { width };
+        let height = if height == 0 { self.natural_height(width) } else { height };
+        self.area = (x, y, width, height);
+
+        // The positioning of the text
+        let mut text_x: u16 = x;
+        let mut text_y: u16 = y;
+
+        // Create the background box, and if there needs to be a border, create
+        // the border.
+        if self.border_.0 {
+
+            // Make sure the text doesn't end up on the border
+            text_x += 1;
+            text_y += 1;
+
+            // Create the bordered box, filling the gap up to the next column.
+            // The border is highlighted if the label has the focus.
+            self.area.2 += 1;
+            let border_fg = if self.focused {
+                self.theme_.get_focus_rgb()
+            }
+            else {
+                self.theme_.get_fg_rgb()
+            };
+            create_border_box(
+                buffer,
+                x,
+                y,
+                width + 1,
+                height,
+                border_fg,
+                self.theme_.get_bg_rgb()
+            );
+        }
+        else {
+            
+            // Create the unbordered box
+            create_fill_box(
+                buffer,
+                x,
+                y,
+                width,
+                height,
+                self.theme_.get_bg_rgb()
+            );
+        }
+
+        // Lay the text out in the room left inside the border, and align it
+        let (room_width, room_height) = match self.border_.0 {
+            true => (width.saturating_sub(1), height.saturating_sub(2)),
+            false => (width, height),
+        };
+        let lines = self.overflow_.fit(
+            self.wrap_.lines(&self.text_, room_width as usize),
+            room_width as usize,
+            room_height as usize
+        );
+        text_y += self.vertical_align_.offset(lines.len() as u16, room_height);
+
+        // Create the label's text
+        for (i, line) in lines.iter().enumerate() {
+            let offset = self.align_.offset(text::width(line) as u16, room_width);
+            buffer.set_string(
+                text_x + offset,
+                text_y + i as u16,
+                line,
+                self.theme_.get_fg_rgb(),
+                self.theme_.get_bg_rgb()
+            );
+        }
+        Ok(())
+    }
+
+    fn draw_dirty(&mut self, buffer: &mut Buffer) -> Result<bool> {
+        let (x, y, width, height) = match self.placement {
+            Some(placement) if self.dirty => placement,
+            _ => return Ok(false),
+        };
+        self.draw(buffer, x, y, width, height)?;
+        Ok(true)
+    }
+
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+    fn skip_draw(&mut self) {
+        self.area = (0, 0, 0, 0);
+        self.placement = None;
+    }
+}
+
+/// A scrollable list of items, one per row. The selected item is highlighted,
+/// and can be changed with the arrow keys, PageUp/PageDown, Home/End, or by
+/// clicking on it; the mouse wheel scrolls the list. With multi-select, Space
+/// or a click toggles whether the item under the cursor is selected.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create the list
+///     let list = ListView::builder()
+///         .items(vec![String::from("Apples"), String::from("Pears")])
+///         .on_select(|_ctx, index| assert!(*index < 2))
+///         .build();
+///     window.grid(Box::new(list), 1, 1, 3, 1);
+///     // window.run();
+/// }
+/// ```
+pub struct ListView {
+    /// The area the list was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the list has a border
+    border_: bool,
+    /// The callbacks run when a key is pressed or the list is clicked
+    callbacks: Callbacks,
+    /// The number of grid columns this list spans
+    colspan: u16,
+    /// The index of the highlighted item
+    cursor: usize,
+    /// Whether the list has the focus
+    focused: bool,
+    /// The items shown in the list, one per row
+    items_: Vec<String>,
+    /// The indices of the selected items when multi-select is on, in order
+    marked: Vec<usize>,
+    /// Whether more than one item can be selected
+    multi_select_: bool,
+    /// The number of grid rows this list spans
+    rowspan: u16,
+    /// The index of the first visible item
+    scroll: usize,
+    /// The callbacks run when the selection changes
+    select_callbacks: Vec<ChangeCallback<usize>>,
+    /// The [`Theme`] that this list uses for its colors
+    theme_: Theme,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl ListView {
+
+    /// Return the index of the highlighted item, or [`None`] if the list is empty
+    pub fn get_cursor(&self) -> Option<usize> {
+        if self.items_.is_empty() { None } else { Some(self.cursor) }
+    }
+
+    /// Return the items shown in the list
+    pub fn get_items(&self) -> &[String] {
+        &self.items_
+    }
+
+    /// Return the indices of the selected items, in order. Without multi-select,
+    /// this is just the highlighted item.
+    pub fn get_selected(&self) -> Vec<usize> {
+        if self.multi_select_ {
+            self.marked.clone()
+        }
+        else {
+            self.get_cursor().into_iter().collect()
+        }
+    }
+
+    /// Return the number of items that fit in the list
+    fn page_size(&self) -> usize {
+        let border = if self.border_ { 2 } else { 0 };
+        (self.area.3.saturating_sub(border) as usize).max(1)
+    }
+
+    /// Scroll the list by `rows` rows, up if negative, without moving past either
+    /// end
+    fn scroll_by(&mut self, rows: isize) {
+        let max = self.items_.len().saturating_sub(self.page_size());
+        self.scroll = self.scroll.saturating_add_signed(rows).min(max);
+    }
+
+    /// Run all the on-select callbacks with `index`
+    fn selected(&mut self, index: usize, ctx: &mut Context) {
+        for callback in &mut self.select_callbacks {
+            callback(ctx, &index);
+        }
+    }
+
+    /// Highlight the item at `index`, scrolling it into view. Without
+    /// multi-select, this also runs the on-select callbacks if it changed.
+    fn set_cursor(&mut self, index: usize, ctx: &mut Context) {
+        if self.items_.is_empty() { return; }
+        let index = index.min(self.items_.len() - 1);
+        let changed = index != self.cursor;
+        self.cursor = index;
+        let page = self.page_size();
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        }
+        else if self.cursor >= self.scroll + page {
+            self.scroll = self.cursor + 1 - page;
+        }
+        if changed && !self.multi_select_ {
+            self.selected(index, ctx);
+        }
+    }
+
+    /// Toggle whether the highlighted item is selected, when multi-select is on
+    fn toggle(&mut self, ctx: &mut Context) {
+        if !self.multi_select_ || self.items_.is_empty() { return; }
+        match self.marked.binary_search(&self.cursor) {
+            Ok(i) => { self.marked.remove(i); },
+            Err(i) => self.marked.insert(i, self.cursor),
+        }
+        self.selected(self.cursor, ctx);
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the list has a border. Use when building the list.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let list = ListView::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> ListView {
+        self.border_ = border;
+        self
+    }
+
+    /// Sets the items shown in the list to `items`, a [`Vec`] of [`String`]s. Use
+    /// when building the list.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let list = ListView::builder()
+    ///     .items(vec![String::from("One"), String::from("Two")])
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn items(mut self, items: Vec<String>) -> ListView {
+        self.items_ = items;
+        self
+    }
+
+    /// Sets whether more than one item can be selected. Use when building the
+    /// list.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let list = ListView::builder()
+    ///     .multi_select(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn multi_select(mut self, multi_select: bool) -> ListView {
+        self.multi_select_ = multi_select;
+        self
+    }
+
+    /// Adds a callback that is run when `key` is pressed while the list has the
+    /// focus, and the list doesn't use the key itself. Use when building the list.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// # 
+    /// # fn main() {
+    /// let list = ListView::builder()
+    ///     .on_key(KeyCode::Enter, |ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> ListView {
+        self.callbacks.on_key(key, callback);
+        self
+    }
+
+    /// Adds a callback that is run with the index of an item whenever it is
+    /// selected; with multi-select, whenever it is selected or unselected. Use when
+    /// building the list.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let list = ListView::builder()
+    ///     .on_select(|_ctx, index| assert!(*index < 10))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_select(mut self, callback: impl FnMut(&mut Context, &usize) + 'static) -> ListView {
+        self.select_callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Sets the list's theme to `theme`, a [`Theme`]. Use when building the list.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let list = ListView::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> ListView {
+        self.theme_ = theme;
+        self
+    }
+}
+impl Buildable for ListView {
+
+    fn build(self) -> ListView {
+        ListView {
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
+            colspan: self.colspan,
+            cursor: self.cursor,
+            focused: self.focused,
+            items_: self.items_,
+            marked: self.marked,
+            multi_select_: self.multi_select_,
+            rowspan: self.rowspan,
+            scroll: self.scroll,
+            select_callbacks: self.select_callbacks,
+            theme_: self.theme_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> ListView {
+        ListView {
+            area: (0, 0, 0, 0),
+            border_: true,
+            callbacks: Callbacks::default(),
+            colspan: 1,
+            cursor: 0,
+            focused: false,
+            items_: Vec::new(),
+            marked: Vec::new(),
+            multi_select_: false,
+            rowspan: 1,
+            scroll: 0,
+            select_callbacks: Vec::new(),
+            theme_: default_theme(),
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> ListView {
+        ListView::builder().build()
+    }
+}
+impl Eventable for ListView {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        let page = self.page_size();
+        match event {
+            Event::Key(key) => match key.code {
+                KeyCode::Up => self.set_cursor(self.cursor.saturating_sub(1), ctx),
+                KeyCode::Down => self.set_cursor(self.cursor + 1, ctx),
+                KeyCode::PageUp => self.set_cursor(self.cursor.saturating_sub(page), ctx),
+                KeyCode::PageDown => self.set_cursor(self.cursor + page, ctx),
+                KeyCode::Home => self.set_cursor(0, ctx),
+                KeyCode::End => self.set_cursor(usize::MAX, ctx),
+                KeyCode::Char(' ') if self.multi_select_ => self.toggle(ctx),
+                _ => return self.callbacks.handle(event, ctx),
+            },
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollUp => self.scroll_by(-1),
+                MouseEventKind::ScrollDown => self.scroll_by(1),
+
+                // Clicking on an item highlights it, and toggles it with
+                // multi-select
+                MouseEventKind::Down(MouseButton::Left) => {
+
+                    // The borders are outside the visible items
+                    let border = if self.border_ { 1 } else { 0 };
+                    let (left, top) = (self.area.0 + border, self.area.1 + border);
+                    let right = (self.area.0 + self.area.2).saturating_sub(border);
+                    let offset = mouse.row.saturating_sub(top) as usize;
+                    let index = self.scroll + offset;
+                    let inside = mouse.column >= left && mouse.column < right && mouse.row >= top;
+                    if inside && offset < self.page_size() && index < self.items_.len() {
+                        self.set_cursor(index, ctx);
+                        self.toggle(ctx);
+                    }
+                    self.callbacks.handle(event, ctx);
+                },
+                _ => return self.callbacks.handle(event, ctx),
+            },
+            _ => return self.callbacks.handle(event, ctx),
+        }
+        true
+    }
+}
+impl Focusable for ListView {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for ListView {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { Some(self) }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+
+        // A size of `0` means the list was placed with `add()`, so use its
+        // natural size: wide enough for the longest item, and up to ten items
+        // tall
+        let border = if self.border_ { 1 } else { 0 };
+        let marker = if self.multi_select_ { 4 } else { 0 };
+        let width = if width == 0 {
+            let longest = self.items_.iter().map(|item| text::width(item)).max().unwrap_or(0);
+            longest as u16 + marker + 2 * border
+        }
+        else {
+            width
+        };
+        let height = if height == 0 {
+            self.items_.len().min(10) as u16 + 2 * border
+        }
+        else {
+            height
+        };
+        self.area = (x, y, width, height);
+
+        // Create the background box, and the border if there is one, highlighted
+        // if the list has the focus
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+        if self.border_ {
+            let border_fg = if self.focused { self.theme_.get_focus_rgb() } else { fg };
+            create_border_box(buffer, x, y, width, height, border_fg, bg);
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, bg);
+        }
+
+        // The list may have gotten taller since it was last scrolled
+        self.scroll_by(0);
+
+        // Create the visible items, highlighting the one under the cursor
+        let text_width = width.saturating_sub(2 * border);
+        let visible = self.items_.iter().enumerate().skip(self.scroll).take(self.page_size());
+        for (row, (i, item)) in visible.enumerate() {
+            let row_y = y + border + row as u16;
+            let row_bg = if i == self.cursor { self.theme_.get_selected_rgb() } else { bg };
+            let text = if !self.multi_select_ {
+                item.clone()
+            }
+            else if self.marked.binary_search(&i).is_ok() {
+                format!("[x] {}", item)
+            }
+            else {
+                format!("[ ] {}", item)
+            };
+            let text = text::clip(&text, text_width as usize);
+            create_fill_box(buffer, x + border, row_y, text_width, 1, row_bg);
+            buffer.set_string(x + border, row_y, text, fg, row_bg);
+        }
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A horizontal progress bar. In determinate mode it fills up from the left as
+/// its value goes from `0.0` to `1.0`, using eighth-block characters so that it
+/// moves smoothly even within a single cell. In indeterminate mode a block
+/// bounces back and forth instead, one step on every tick of the window, or each
+/// time [`ProgressBar::pulse()`] is called. The bar can show a percentage or a
+/// custom label in the middle.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create a progress bar that shows how far along it is
+///     let progress = ProgressBar::builder()
+///         .value(0.25)
+///         .percentage(true)
+///         .build();
+///     window.grid(Box::new(progress), 1, 1, 1, 3);
+///     // window.run();
+/// }
+/// ```
+pub struct ProgressBar {
+    /// The area the bar was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the bar has a border
+    border_: bool,
+    /// The number of grid columns this bar spans
+    colspan: u16,
+    /// Whether the bar shows a bouncing block instead of its value
+    indeterminate_: bool,
+    /// The text shown in the middle of the bar, if there is one
+    label_: Option<String>,
+    /// Whether the percentage is shown in the middle of the bar, when there is no
+    /// label
+    percentage_: bool,
+    /// The number of times the bouncing block has moved
+    pulses: usize,
+    /// The number of grid rows this bar spans
+    rowspan: u16,
+    /// The [`Theme`] that this bar uses for its colors
+    theme_: Theme,
+    /// How far along the bar is, from `0.0` to `1.0`
+    value_: f64,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl ProgressBar {
+
+    /// Return how far along the bar is, from `0.0` to `1.0`
+    pub fn get_value(&self) -> f64 {
+        self.value_
+    }
+
+    /// Move the bouncing block of an indeterminate bar one step
+    pub fn pulse(&mut self) {
+        self.pulses = self.pulses.wrapping_add(1);
+    }
+
+    /// Set how far along the bar is to `value`, clamped between `0.0` and `1.0`
+    pub fn set_value(&mut self, value: f64) {
+        self.value_ = value.clamp(0.0, 1.0);
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the bar has a border. Use when building the bar.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let progress = ProgressBar::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> ProgressBar {
+        self.border_ = border;
+        self
+    }
+
+    /// Sets whether the bar shows a bouncing block instead of its value, for work
+    /// of unknown length. Use when building the bar.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let progress = ProgressBar::builder()
+    ///     .indeterminate(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn indeterminate(mut self, indeterminate: bool) -> ProgressBar {
+        self.indeterminate_ = indeterminate;
+        self
+    }
+
+    /// Sets the text shown in the middle of the bar to `label`, a [`String`],
+    /// instead of the percentage. Use when building the bar.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let progress = ProgressBar::builder()
+    ///     .label(String::from("Downloading..."))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn label(mut self, label: String) -> ProgressBar {
+        self.label_ = Some(label);
+        self
+    }
+
+    /// Sets whether the percentage is shown in the middle of the bar. Use when
+    /// building the bar.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let progress = ProgressBar::builder()
+    ///     .percentage(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn percentage(mut self, percentage: bool) -> ProgressBar {
+        self.percentage_ = percentage;
+        self
+    }
+
+    /// Sets the bar's theme to `theme`, a [`Theme`]. The bar is drawn in the
+    /// theme's foreground color. Use when building the bar.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let progress = ProgressBar::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> ProgressBar {
+        self.theme_ = theme;
+        self
+    }
+
+    /// Sets how far along the bar is at first to `value`, from `0.0` to `1.0`.
+    /// Use when building the bar.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let progress = ProgressBar::builder()
+    ///     .value(0.5)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn value(mut self, value: f64) -> ProgressBar {
+        self.set_value(value);
+        self
+    }
+}
+impl Buildable for ProgressBar {
+
+    fn build(self) -> ProgressBar {
+        ProgressBar {
+            area: self.area,
+            border_: self.border_,
+            colspan: self.colspan,
+            indeterminate_: self.indeterminate_,
+            label_: self.label_,
+            percentage_: self.percentage_,
+            pulses: self.pulses,
+            rowspan: self.rowspan,
+            theme_: self.theme_,
+            value_: self.value_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> ProgressBar {
+        ProgressBar {
+            area: (0, 0, 0, 0),
+            border_: true,
+            colspan: 1,
+            indeterminate_: false,
+            label_: None,
+            percentage_: false,
+            pulses: 0,
+            rowspan: 1,
+            theme_: default_theme(),
+            value_: 0.0,
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> ProgressBar {
+        ProgressBar::builder().build()
+    }
+}
+impl Eventable for ProgressBar {
+    fn event_send(&mut self, _event: Event, _ctx: &mut Context) -> bool { false }
+
+    fn tick(&mut self, _ctx: &mut Context) -> bool {
+        if self.indeterminate_ { self.pulse(); }
+        self.indeterminate_
+    }
+}
+impl Widget for ProgressBar {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+
+        // A size of `0` means the bar was placed with `add()`, so use its natural
+        // size
+        let border = if self.border_ { 1 } else { 0 };
+        let width = if width == 0 { 20 + 2 * border } else { width };
+        let height = if height == 0 { 1 + 2 * border } else { height };
+        self.area = (x, y, width, height);
+
+        // Create the background box, and the border if there is one
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+        if self.border_ {
+            create_border_box(buffer, x, y, width, height, fg, bg);
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, bg);
+        }
+        let inner_x = x + border;
+        let inner_y = y + border;
+        let inner_width = width.saturating_sub(2 * border);
+        let inner_height = height.saturating_sub(2 * border);
+
+        // Work out which cells are filled. An indeterminate bar has a block a
+        // quarter of its width bouncing between its ends; a determinate one is
+        // filled from the left in eighths of a cell, with the partly filled cell
+        // using a block character.
+        let (start, full) = if self.indeterminate_ {
+            let block = (inner_width / 4).max(1);
+            let travel = inner_width.saturating_sub(block) as usize;
+            let step = if travel == 0 { 0 } else { self.pulses % (2 * travel) };
+            let start = if step <= travel { step } else { 2 * travel - step };
+            (start as u16, block.min(inner_width))
+        }
+        else {
+            let eighths = (self.value_ * inner_width as f64 * 8.0).round() as u16;
+            let (full, partial) = (eighths / 8, eighths % 8);
+            if partial > 0 && full < inner_width {
+                let block = chars::BLOCKS_HORIZONTAL[partial as usize - 1];
+                for row in 0..inner_height {
+                    buffer.set_string(inner_x + full, inner_y + row, block, fg, bg);
+                }
+            }
+            (0, full)
+        };
+        create_fill_box(buffer, inner_x + start, inner_y, full, inner_height, fg);
+
+        // Create the label in the middle, inverting its colors over the filled
+        // cells so that it stays readable
+        let value = if self.indeterminate_ { None } else { Some(self.value_) };
+        let label = match value {
+            Some(value) => progress_label(&self.label_, self.percentage_, value),
+            None => self.label_.clone(),
+        };
+        if let Some(label) = label {
+            let label = text::truncate(&label, inner_width as usize);
+            let mut column = inner_x + (inner_width - text::width(&label) as u16) / 2;
+            let label_y = inner_y + inner_height.saturating_sub(1) / 2;
+            for grapheme in text::graphemes(&label) {
+                let filled = column >= inner_x + start && column < inner_x + start + full;
+                let (label_fg, label_bg) = if filled { (bg, fg) } else { (fg, bg) };
+                buffer.set_string(column, label_y, grapheme, label_fg, label_bg);
+                column += text::grapheme_width(grapheme) as u16;
+            }
+        }
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A table of rows of text under a header row. Each column has a
+/// [`ColumnWidth`] rule, and text that doesn't fit in its column is cut off
+/// with an ellipsis. Rows are selected like in a [`ListView`], and clicking on
+/// a header sorts the rows by that column; clicking it again reverses the order.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create the table
+///     let table = Table::builder()
+///         .column(String::from("Name"), ColumnWidth::Fill)
+///         .column(String::from("Size"), ColumnWidth::Fixed(8))
+///         .rows(vec![
+///             vec![String::from("notes.txt"), String::from("12")],
+///             vec![String::from("photo.png"), String::from("2048")],
+///         ])
+///         .zebra(true)
+///         .build();
+///     window.grid(Box::new(table), 1, 1, 3, 3);
+///     // window.run();
+/// }
+/// ```
+pub struct Table {
+    /// The area the table was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the table has a border
+    border_: bool,
+    /// The callbacks run when a key is pressed or the table is clicked
+    callbacks: Callbacks,
+    /// The number of grid columns this table spans
+    colspan: u16,
+    /// The header and width rule of each column
+    columns: Vec<(String, ColumnWidth)>,
+    /// The index of the selected row
+    cursor: usize,
+    /// Whether the table has the focus
+    focused: bool,
+    /// The number of grid rows this table spans
+    rowspan: u16,
+    /// The rows of the table, each with one [`String`] per column
+    rows_: Vec<Vec<String>>,
+    /// The index of the first visible row
+    scroll: usize,
+    /// The callbacks run when the selected row changes
+    select_callbacks: Vec<ChangeCallback<usize>>,
+    /// The column the rows are sorted by, and whether the order is ascending
+    sort: Option<(usize, bool)>,
+    /// The [`Theme`] that this table uses for its colors
+    theme_: Theme,
+    /// The width of each column when the table was last drawn, in chars
+    widths: Vec<u16>,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+    /// Whether every other row has a different background color
+    zebra_: bool,
+}
+impl Table {
+
+    /// Return the index of the selected row, or [`None`] if the table is empty
+    pub fn get_cursor(&self) -> Option<usize> {
+        if self.rows_.is_empty() { None } else { Some(self.cursor) }
+    }
+
+    /// Return the rows of the table, in the order they are shown
+    pub fn get_rows(&self) -> &[Vec<String>] {
+        &self.rows_
+    }
+
+    /// Create the text of `cells` on row `y`, each cut off to the width of its
+    /// column, using the background color `bg`
+    fn draw_row(&self, buffer: &mut Buffer, cells: &[String], y: u16, bg: Color) {
+        let border = if self.border_ { 1 } else { 0 };
+        let right = self.area.0 + self.area.2.saturating_sub(border);
+        let mut left = self.area.0 + border;
+        for (cell, width) in cells.iter().zip(&self.widths) {
+            if left >= right { break; }
+            let width = (*width).min(right - left);
+            let text = text::truncate(cell, width as usize);
+            buffer.set_string(left, y, &text, self.theme_.get_fg_rgb(), bg);
+            left += width + 1;
+        }
+    }
+
+    /// Return the number of rows that fit under the header
+    fn page_size(&self) -> usize {
+        let border = if self.border_ { 2 } else { 0 };
+        (self.area.3.saturating_sub(border + 1) as usize).max(1)
+    }
+
+    /// Scroll the table by `rows` rows, up if negative, without moving past
+    /// either end
+    fn scroll_by(&mut self, rows: isize) {
+        let max = self.rows_.len().saturating_sub(self.page_size());
+        self.scroll = self.scroll.saturating_add_signed(rows).min(max);
+    }
+
+    /// Select the row at `index`, scrolling it into view, and run the on-select
+    /// callbacks if it changed
+    fn set_cursor(&mut self, index: usize, ctx: &mut Context) {
+        if self.rows_.is_empty() { return; }
+        let index = index.min(self.rows_.len() - 1);
+        let changed = index != self.cursor;
+        self.cursor = index;
+        let page = self.page_size();
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        }
+        else if self.cursor >= self.scroll + page {
+            self.scroll = self.cursor + 1 - page;
+        }
+        if changed {
+            for callback in &mut self.select_callbacks {
+                callback(ctx, &index);
+            }
+        }
+    }
+
+    /// Sort the rows by column `column`, reversing the order if they already are.
+    /// Values that are all numbers are sorted numerically. The same row stays
+    /// selected.
+    fn sort_by(&mut self, column: usize) {
+        let ascending = self.sort != Some((column, true));
+        let mut order: Vec<usize> = (0..self.rows_.len()).collect();
+        order.sort_by(|&a, &b| {
+            let a = self.rows_[a].get(column).map_or("", String::as_str);
+            let b = self.rows_[b].get(column).map_or("", String::as_str);
+            let ordering = match (a.parse::<f64>(), b.parse::<f64>()) {
+                (Ok(a), Ok(b)) => a.total_cmp(&b),
+                _ => a.cmp(b),
+            };
+            if ascending { ordering } else { ordering.reverse() }
+        });
+        let mut rows: Vec<Option<Vec<String>>> = self.rows_.drain(..).map(Some).collect();
+        self.rows_ = order.iter().map(|&i| rows[i].take().unwrap_or_default()).collect();
+        self.cursor = order.iter().position(|&i| i == self.cursor).unwrap_or(0);
+        self.sort = Some((column, ascending));
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the table has a border. Use when building the table.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let table = Table::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> Table {
+        self.border_ = border;
+        self
+    }
+
+    /// Adds a column with the header `header` and the width rule `width`. Use
+    /// when building the table.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let table = Table::builder()
+    ///     .column(String::from("Name"), ColumnWidth::Percent(60))
+    ///     .column(String::from("Owner"), ColumnWidth::Fill)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn column(mut self, header: String, width: ColumnWidth) -> Table {
+        self.columns.push((header, width));
+        self
+    }
+
+    /// Adds a callback that is run when `key` is pressed while the table has the
+    /// focus, and the table doesn't use the key itself. Use when building the
+    /// table.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// # 
+    /// # fn main() {
+    /// let table = Table::builder()
+    ///     .on_key(KeyCode::Enter, |ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> Table {
+        self.callbacks.on_key(key, callback);
+        self
+    }
+
+    /// Adds a callback that is run with the index of a row whenever it is
+    /// selected. Use when building the table.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let table = Table::builder()
+    ///     .on_select(|_ctx, row| assert!(*row < 10))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_select(mut self, callback: impl FnMut(&mut Context, &usize) + 'static) -> Table {
+        self.select_callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Sets the rows of the table to `rows`, each with one [`String`] per column.
+    /// Use when building the table.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let table = Table::builder()
+    ///     .column(String::from("Name"), ColumnWidth::Fill)
+    ///     .rows(vec![vec![String::from("Alice")], vec![String::from("Bob")]])
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn rows(mut self, rows: Vec<Vec<String>>) -> Table {
+        self.rows_ = rows;
+        self
+    }
+
+    /// Sets the table's theme to `theme`, a [`Theme`]. Use when building the
+    /// table.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let table = Table::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> Table {
+        self.theme_ = theme;
+        self
+    }
+
+    /// Sets whether every other row uses the theme's stripe color for its
+    /// background. Use when building the table.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let table = Table::builder()
+    ///     .zebra(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn zebra(mut self, zebra: bool) -> Table {
+        self.zebra_ = zebra;
+        self
+    }
+}
+impl Buildable for Table {
+
+    fn build(self) -> Table {
+        Table {
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
+            colspan: self.colspan,
+            columns: self.columns,
+            cursor: self.cursor,
+            focused: self.focused,
+            rowspan: self.rowspan,
+            rows_: self.rows_,
+            scroll: self.scroll,
+            select_callbacks: self.select_callbacks,
+            sort: self.sort,
+            theme_: self.theme_,
+            widths: self.widths,
+            x: self.x,
+            y: self.y,
+            zebra_: self.zebra_,
+        }
+    }
+
+    fn builder() -> Table {
+        Table {
+            area: (0, 0, 0, 0),
+            border_: true,
+            callbacks: Callbacks::default(),
+            colspan: 1,
+            columns: Vec::new(),
+            cursor: 0,
+            focused: false,
+            rowspan: 1,
+            rows_: Vec::new(),
+            scroll: 0,
+            select_callbacks: Vec::new(),
+            sort: None,
+            theme_: default_theme(),
+            widths: Vec::new(),
+            x: 1,
+            y: 1,
+            zebra_: false,
+        }
+    }
+
+    fn new() -> Table {
+        Table::builder().build()
+    }
+}
+impl Eventable for Table {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        let page = self.page_size();
+        match event {
+            Event::Key(key) => match key.code {
+                KeyCode::Up => self.set_cursor(self.cursor.saturating_sub(1), ctx),
+                KeyCode::Down => self.set_cursor(self.cursor + 1, ctx),
+                KeyCode::PageUp => self.set_cursor(self.cursor.saturating_sub(page), ctx),
+                KeyCode::PageDown => self.set_cursor(self.cursor + page, ctx),
+                KeyCode::Home => self.set_cursor(0, ctx),
+                KeyCode::End => self.set_cursor(usize::MAX, ctx),
+                _ => return self.callbacks.handle(event, ctx),
+            },
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollUp => self.scroll_by(-1),
+                MouseEventKind::ScrollDown => self.scroll_by(1),
+
+                // Clicking on a header sorts by its column, and clicking on a
+                // row selects it
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let border = if self.border_ { 1 } else { 0 };
+                    let header_y = self.area.1 + border;
+                    if mouse.row == header_y {
+                        let mut left = self.area.0 + border;
+                        for (i, width) in self.widths.clone().into_iter().enumerate() {
+                            if mouse.column >= left && mouse.column < left + width {
+                                self.sort_by(i);
+                                break;
+                            }
+                            left += width + 1;
+                        }
+                    }
+                    else if mouse.row > header_y {
+
+                        // The bottom border is below the last visible row
+                        let offset = (mouse.row - header_y - 1) as usize;
+                        let index = self.scroll + offset;
+                        if offset < page && index < self.rows_.len() {
+                            self.set_cursor(index, ctx);
+                        }
+                    }
+                    self.callbacks.handle(event, ctx);
+                },
+                _ => return self.callbacks.handle(event, ctx),
+            },
+            _ => return self.callbacks.handle(event, ctx),
+        }
+        true
+    }
+}
+impl Focusable for Table {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for Table {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { Some(self) }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+
+        // A size of `0` means the table was placed with `add()`, so use its
+        // natural size: up to ten rows tall
+        let border = if self.border_ { 1 } else { 0 };
+        let width = if width == 0 { 40 } else { width };
+        let height = if height == 0 {
+            self.rows_.len().min(10) as u16 + 1 + 2 * border
+        }
+        else {
+            height
+        };
+        self.area = (x, y, width, height);
+
+        // Create the background box, and the border if there is one, highlighted
+        // if the table has the focus
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+        if self.border_ {
+            let border_fg = if self.focused { self.theme_.get_focus_rgb() } else { fg };
+            create_border_box(buffer, x, y, width, height, border_fg, bg);
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, bg);
+        }
+
+        // Work out the width of each column
+        let text_x = x + border;
+        let text_width = width.saturating_sub(2 * border);
+        let rules: Vec<ColumnWidth> = self.columns.iter().map(|column| column.1).collect();
+        self.widths = ColumnWidth::resolve(&rules, text_width);
+
+        // Create the header, with an arrow on the column the rows are sorted by
+        let headers: Vec<String> = self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, (header, _))| match self.sort {
+                Some((column, ascending)) if column == i => {
+                    let arrow = if ascending {
+                        chars::SORT_ASCENDING
+                    }
+                    else {
+                        chars::SORT_DESCENDING
+                    };
+                    format!("{} {}", header, arrow)
+                },
+                _ => header.clone(),
+            })
+            .collect();
+        self.draw_row(buffer, &headers, y + border, bg);
+        buffer.set_attribute(text_x, y + border, text_width, Attribute::Bold);
+
+        // The table may have gotten taller since it was last scrolled
+        self.scroll_by(0);
+
+        // Create the visible rows, highlighting the selected one
+        let visible = self.page_size().min(self.rows_.len().saturating_sub(self.scroll));
+        for row in 0..visible {
+            let index = self.scroll + row;
+            let row_bg = if index == self.cursor {
+                self.theme_.get_selected_rgb()
+            }
+            else if self.zebra_ && index % 2 == 1 {
+                self.theme_.get_stripe_rgb()
+            }
+            else {
+                bg
+            };
+            let row_y = y + border + 1 + row as u16;
+            create_fill_box(buffer, text_x, row_y, text_width, 1, row_bg);
+            self.draw_row(buffer, &self.rows_[index], row_y, row_bg);
+        }
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A container widget with several pages of children, and a tab bar across the
+/// top to switch between them. Only the active page is drawn, laid out in the
+/// area below the tab bar using the page's own [`Grid`].
+/// 
+/// Pages are switched by clicking on their tab, with Ctrl+PageUp and
+/// Ctrl+PageDown, or with the number keys `1` to `9`. Children added with
+/// [`Parent::add()`] and [`Parent::grid()`] go on the active page.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create two pages with a label on each
+///     let general = Label::builder().text(String::from("General")).build();
+///     let advanced = Label::builder().text(String::from("Advanced")).build();
+///     let mut tabs = Tabs::builder()
+///         .page(String::from("General"), Grid::new())
+///         .page(String::from("Advanced"), Grid::new())
+///         .build();
+///     tabs.grid(Box::new(general), 1, 1, 1, 2);
+///     let mut tabs = tabs.set_active(1);
+///     tabs.grid(Box::new(advanced), 1, 1, 1, 2);
+///     window.grid(Box::new(tabs.set_active(0)), 1, 1, 3, 3);
+///     // window.run();
+/// }
+/// ```
+pub struct Tabs {
+    /// The index of the page that is shown
+    active: usize,
+    /// The area the tabs were last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the pages have a border
+    border_: bool,
+    /// The callbacks run when the user interacts with the tabs, or when an event
+    /// the children didn't handle bubbles up to them
+    callbacks: Callbacks,
+    /// The number of grid columns these tabs span
+    colspan: u16,
+    /// The error from a child that was added while there was no page to hold
+    /// it, which the next draw returns
+    error: Option<Error>,
+    /// Whether the tab bar has the focus
+    focused: bool,
+    /// The pages, in the order their tabs are shown
+    pages: Vec<TabPage>,
+    /// The number of grid rows these tabs span
+    rowspan: u16,
+    /// The [`Theme`] that these tabs use for their colors
+    theme_: Theme,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl Tabs {
+
+    /// Return the index of the page that is shown
+    pub fn get_active(&self) -> usize {
+        self.active
+    }
+
+    /// Switch to the page at `index`, taking the focus and the hover highlight
+    /// away from the children of the page that gets hidden. Returns whether the
+    /// page changed.
+    fn switch(&mut self, index: usize, ctx: &mut Context) -> bool {
+        if index == self.active || index >= self.pages.len() { return false; }
+        hide(self.children_mut());
+        self.active = index;
+        ctx.focus();
+        true
+    }
+
+    /// Return the index of the tab at column `column` of the tab bar, if there is
+    /// one
+    fn tab_at(&self, column: u16) -> Option<usize> {
+        let mut left = self.area.0;
+        for (i, page) in self.pages.iter().enumerate() {
+            let width = text::width(&page.title) as u16 + 2;
+            if column >= left && column < left + width {
+                return Some(i);
+            }
+            left += width + 1;
+        }
+        None
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the pages have a border. Use when building the tabs.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tabs = Tabs::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> Tabs {
+        self.border_ = border;
+        self
+    }
+
+    /// Adds a callback that is run when `key` is pressed while the tabs or one of
+    /// their children have the focus, and nothing else handles the key. Use when
+    /// building the tabs.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// # 
+    /// # fn main() {
+    /// let tabs = Tabs::builder()
+    ///     .on_key(KeyCode::Esc, |ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> Tabs {
+        self.callbacks.on_key(key, callback);
+        self
+    }
+
+    /// Adds a page with the tab title `title`, laying out its children with
+    /// `grid`. Use when building the tabs.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tabs = Tabs::builder()
+    ///     .page(String::from("Logs"), Grid::builder().width(1).height(1).build())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn page(mut self, title: String, grid: Grid) -> Tabs {
+        self.pages.push(TabPage {
+            absolute_children: Vec::new(),
+            children: Vec::new(),
+            grid,
+            title,
+        });
+        self
+    }
+
+    /// Shows the page at `index`; children added afterwards go on that page.
+    /// Indices past the last page are ignored. Use when building the tabs, as
+    /// the focus isn't moved off the page that gets hidden; the user switches
+    /// pages with the tab bar.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tabs = Tabs::builder()
+    ///     .page(String::from("One"), Grid::new())
+    ///     .page(String::from("Two"), Grid::new())
+    ///     .set_active(1)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_active(mut self, index: usize) -> Tabs {
+        if index < self.pages.len() {
+            self.active = index;
+        }
+        self
+    }
+
+    /// Sets the tabs' theme to `theme`, a [`Theme`]. Use when building the tabs.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tabs = Tabs::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> Tabs {
+        self.theme_ = theme;
+        self
+    }
+}
+impl Buildable for Tabs {
+
+    fn build(self) -> Tabs {
+        Tabs {
+            active: self.active,
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
+            colspan: self.colspan,
+            error: self.error,
+            focused: self.focused,
+            pages: self.pages,
+            rowspan: self.rowspan,
+            theme_: self.theme_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> Tabs {
+        Tabs {
+            active: 0,
+            area: (0, 0, 0, 0),
+            border_: true,
+            callbacks: Callbacks::default(),
+            colspan: 1,
+            error: None,
+            focused: false,
+            pages: Vec::new(),
+            rowspan: 1,
+            theme_: default_theme(),
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> Tabs {
+        Tabs::builder().build()
+    }
+}
+impl Parent for Tabs {
+    fn add_with_id(&mut self, id: WidgetId, child: Box<dyn Widget>, x: u16, y: u16) {
+        match self.pages.get_mut(self.active) {
+            Some(page) => add_child(&mut page.absolute_children, id, child, x, y),
+            None => {
+                self.error.get_or_insert(Error::NoPage);
+            }
+        }
+    }
+
+    fn can_hold(&self) -> bool {
+        !self.pages.is_empty()
+    }
+
+    fn grid_with_id(&mut self,
+        id: WidgetId,
+        child: Box<dyn Widget>,
+        row: u16,
+        col: u16,
+        rowspan: u16,
+        colspan: u16) {
+        match self.pages.get_mut(self.active) {
+            Some(page) => grid_child(&mut page.children, id, child, row, col, rowspan, colspan),
+            None => {
+                self.error.get_or_insert(Error::NoPage);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: WidgetId) -> Option<Box<dyn Widget>> {
+        self.pages.iter_mut().find_map(|page| {
+            remove_child([&mut page.children, &mut page.absolute_children], id)
+        })
+    }
+}
+impl Eventable for Tabs {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        let count = self.pages.len();
+        match event {
+            Event::Key(KeyEvent { code, modifiers }) => {
+                let control = modifiers.contains(KeyModifiers::CONTROL);
+                match code {
+                    KeyCode::PageDown if control && count > 0 => {
+                        self.switch((self.active + 1) % count, ctx)
+                    },
+                    KeyCode::PageUp if control && count > 0 => {
+                        self.switch((self.active + count - 1) % count, ctx)
+                    },
+                    KeyCode::Char(c @ '1'..='9') if !control => {
+                        let index = c as usize - '1' as usize;
+                        self.switch(index, ctx) || self.callbacks.handle(event, ctx)
+                    },
+                    _ => self.callbacks.handle(event, ctx),
+                }
+            },
+
+            // Clicking on a tab shows its page
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) if row == self.area.1 => {
+                if let Some(index) = self.tab_at(column) {
+                    self.switch(index, ctx);
+                }
+                self.callbacks.handle(event, ctx);
+                true
+            },
+            _ => self.callbacks.handle(event, ctx),
+        }
+    }
+}
+impl Focusable for Tabs {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for Tabs {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { Some(self) }
+
+    fn as_parent(&mut self) -> Option<&mut dyn Parent> { Some(self) }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        match self.pages.get_mut(self.active) {
+            Some(page) => page.children.iter_mut()
+                .chain(page.absolute_children.iter_mut())
+                .map(|(_, child)| &mut **child as &mut dyn Widget)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+        self.area = (x, y, width, height);
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+
+        // Create the tab bar, highlighting the active tab, and the tab bar's
+        // focus color if it has the focus
+        create_fill_box(buffer, x, y, width, 1, bg);
+        let mut left = x;
+        for (i, page) in self.pages.iter().enumerate() {
+            let title = format!(" {} ", page.title);
+            let title_width = text::width(&title) as u16;
+            if i == self.active {
+                let title_fg = if self.focused { self.theme_.get_focus_rgb() } else { fg };
+                let title_bg = self.theme_.get_selected_rgb();
+                buffer.set_string(left, y, &title, title_fg, title_bg);
+                buffer.set_attribute(left, y, title_width, Attribute::Bold);
+            }
+            else {
+                buffer.set_string(left, y, &title, fg, bg);
+            }
+            buffer.set_string(left + title_width, y, chars::VERTICAL, fg, bg);
+            left += title_width + 1;
+        }
+
+        // Create the page's background box, and if there needs to be a border,
+        // the border
+        let page_y = y + 1;
+        let page_height = height.saturating_sub(1);
+        let page = match self.pages.get_mut(self.active) {
+            Some(page) => page,
+            None => return Ok(()),
+        };
+        if self.border_ {
+            create_border_box(buffer, x, page_y, width, page_height, fg, bg);
+
+            // Like in a frame, children fill the gap to their right, so take one
+            // more char away to keep them off the right-hand border
+            page.grid.set_width_chars(width.saturating_sub(1));
+        }
+        else {
+            create_fill_box(buffer, x, page_y, width, page_height, bg);
+            page.grid.set_width_chars(width);
+        }
+        page.grid.set_height_chars(page_height);
+
+        // Draw the active page's gridded children, relative to the page's top left
+        // corner
+        let page_area = (x, page_y, width, page_height);
+        draw_grid(&mut page.children, &page.grid, buffer, page_area)?;
+
+        // Draw the children that were placed in characters at their natural size
+        for (_, child) in &mut page.absolute_children {
+            let (child_x, child_y) = (child.get_x(), child.get_y());
+            child.draw(buffer, x + child_x, page_y + child_y, 0, 0)?;
+        }
+        Ok(())
+    }
+
+    fn find(&self, id: WidgetId) -> Option<&(dyn Widget + 'static)> {
+        self.pages.iter().find_map(|page| {
+            find_child([&page.children, &page.absolute_children], id)
+        })
+    }
+
+    fn find_mut(&mut self, id: WidgetId) -> Option<&mut (dyn Widget + 'static)> {
+        self.pages.iter_mut().find_map(|page| {
+            find_child_mut([&mut page.children, &mut page.absolute_children], id)
+        })
+    }
+
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A page of [`Tabs`]: its tab title and its children
+struct TabPage {
+    /// The children added with [`Parent::add()`], placed in characters relative
+    /// to the page's top left corner
+    absolute_children: Children,
+    /// All the gridded children of this page
+    children: Children,
+    /// The [`Grid`] that manages the sizing of this page's children
+    grid: Grid,
+    /// The title shown on this page's tab
+    title: String,
+}
+
+/// The number of edits that a [`TextArea`] remembers for undoing
+const UNDO_LIMIT: usize = 100;
+
+/// A multi-line text editor. The text scrolls both ways to keep the cursor in
+/// view, or can be soft wrapped at the edge of the area instead. Text can be
+/// selected with Shift and the arrow keys, and edits can be undone with Ctrl+Z
+/// and redone with Ctrl+Y. A run of typed chars is undone in one step.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create an editor for a config snippet
+///     let editor = TextArea::builder()
+///         .text(String::from("[server]\nport = 8080"))
+///         .line_numbers(true)
+///         .build();
+///     window.grid(Box::new(editor), 1, 1, 3, 3);
+///     // window.run();
+/// }
+/// ```
+pub struct TextArea {
+    /// The position the selection started at, if there is a selection
+    anchor: Option<(usize, usize)>,
+    /// The area the text area was last drawn in, as an `(x, y, width, height)`
+    /// tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the text area has a border
+    border_: bool,
+    /// The callbacks run when a key is pressed or the text area is clicked
+    callbacks: Callbacks,
+    /// The callbacks run when the text changes
+    change_callbacks: Vec<ChangeCallback<str>>,
+    /// The number of grid columns this text area spans
+    colspan: u16,
+    /// The position of the cursor, as a `(line, char)` tuple
+    cursor: (usize, usize),
+    /// Whether the text area has the focus
+    focused: bool,
+    /// Whether line numbers are shown in front of the lines
+    line_numbers_: bool,
+    /// The lines of text, without their line endings
+    lines: Vec<String>,
+    /// The states that were undone, most recent last
+    redo: Vec<TextAreaState>,
+    /// The number of grid rows this text area spans
+    rowspan: u16,
+    /// The first visible row and the first visible char of each row
+    scroll: (usize, usize),
+    /// The [`Theme`] that this text area uses for its colors
+    theme_: Theme,
+    /// Whether the last key typed a char, so that a char typed next joins its
+    /// undo step
+    typing: bool,
+    /// The states before each edit, most recent last, up to [`UNDO_LIMIT`] of
+    /// them
+    undo: Vec<TextAreaState>,
+    /// Whether long lines are wrapped instead of scrolled horizontally
+    wrap_: bool,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl TextArea {
+
+    /// Return the selected text, if there is a selection
+    pub fn get_selection(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        let text = self.lines[start.0..=end.0]
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let from = if i == 0 { start.1 } else { 0 };
+                let to = if start.0 + i == end.0 { end.1 } else { usize::MAX };
+                text::graphemes(line).skip(from).take(to - from).collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        Some(text)
+    }
+
+    /// Return the whole text, with the lines separated by `\n`
+    pub fn get_text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Run all the on-change callbacks with the current text
+    fn changed(&mut self, ctx: &mut Context) {
+        let text = self.get_text();
+        for callback in &mut self.change_callbacks {
+            callback(ctx, &text);
+        }
+    }
+
+    /// Delete the selected text, leaving the cursor where it started. Returns
+    /// whether there was anything to delete.
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = match self.selection() {
+            Some(selection) => selection,
+            None => {
+                self.anchor = None;
+                return false;
+            }
+        };
+        let head = &self.lines[start.0][..text::byte_index(&self.lines[start.0], start.1)];
+        let tail = &self.lines[end.0][text::byte_index(&self.lines[end.0], end.1)..];
+        let joined = head.to_string() + tail;
+        self.lines.drain((start.0 + 1)..=end.0);
+        self.lines[start.0] = joined;
+        self.cursor = start;
+        self.anchor = None;
+        true
+    }
+
+    /// Return the width of the line number gutter, in chars
+    fn gutter_width(&self) -> u16 {
+        if self.line_numbers_ { self.lines.len().to_string().len() as u16 + 1 } else { 0 }
+    }
+
+    /// Handle a key press, editing the text or moving the cursor. Returns whether
+    /// the key was handled.
+    fn key(&mut self, key: KeyEvent, ctx: &mut Context) -> bool {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+
+        // Undo and redo restore whole states, so they don't go through the
+        // change tracking below
+        match key.code {
+            KeyCode::Char('z') if control => {
+                self.restore(true, ctx);
+                return true;
+            },
+            KeyCode::Char('y') if control => {
+                self.restore(false, ctx);
+                return true;
+            },
+            _ => {},
+        }
+
+        // Only edits need the state before them, and a char typed right after
+        // another one, with nothing selected, joins the undo step of the first
+        let typed = matches!(key.code, KeyCode::Char(_))
+            && !control
+            && !key.modifiers.contains(KeyModifiers::ALT);
+        let joins = typed && self.typing && self.selection().is_none();
+        let editing = typed || matches!(
+            key.code,
+            KeyCode::Enter | KeyCode::Backspace | KeyCode::Delete
+        );
+        let before = if editing && !joins { Some(self.state()) } else { None };
+        match key.code {
+            KeyCode::Char('a') if control => {
+                self.anchor = Some((0, 0));
+                self.cursor = (self.lines.len() - 1, self.line_len(self.lines.len() - 1));
+            },
+            KeyCode::Char(c) if !control && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.delete_selection();
+                let (line, column) = self.cursor;
+                let i = text::byte_index(&self.lines[line], column);
+                self.lines[line].insert(i, c);
+
+                // A combining char joins the grapheme before it instead of
+                // making a new one
+                self.cursor.1 = text::count(&self.lines[line][..i + c.len_utf8()]);
+            },
+            KeyCode::Enter => {
+                self.delete_selection();
+                let (line, column) = self.cursor;
+                let i = text::byte_index(&self.lines[line], column);
+                let tail = self.lines[line].split_off(i);
+                self.lines.insert(line + 1, tail);
+                self.cursor = (line + 1, 0);
+            },
+            KeyCode::Backspace => {
+                if !self.delete_selection() {
+                    let (line, column) = self.cursor;
+                    if column > 0 {
+                        let i = text::byte_index(&self.lines[line], column - 1);
+                        let j = text::byte_index(&self.lines[line], column);
+                        self.lines[line].replace_range(i..j, "");
+                        self.cursor.1 -= 1;
+                    }
+                    else if line > 0 {
+                        let removed = self.lines.remove(line);
+                        self.cursor = (line - 1, self.line_len(line - 1));
+                        self.lines[line - 1].push_str(&removed);
+                    }
+                }
+            },
+            KeyCode::Delete => {
+                if !self.delete_selection() {
+                    let (line, column) = self.cursor;
+                    if column < self.line_len(line) {
+                        let i = text::byte_index(&self.lines[line], column);
+                        let j = text::byte_index(&self.lines[line], column + 1);
+                        self.lines[line].replace_range(i..j, "");
+                    }
+                    else if line + 1 < self.lines.len() {
+                        let removed = self.lines.remove(line + 1);
+                        self.lines[line].push_str(&removed);
+                    }
+                }
+            },
+            KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Home
+            | KeyCode::End
+            | KeyCode::PageUp
+            | KeyCode::PageDown => {
+
+                // Holding Shift starts or extends the selection, anything else
+                // clears it
+                if !shift {
+                    self.anchor = None;
+                }
+                else if self.anchor.is_none() {
+                    self.anchor = Some(self.cursor);
+                }
+                self.move_cursor(key.code, control);
+            },
+            _ => return false,
+        }
+
+        // Remember the state before every edit, so that it can be undone,
+        // forgetting the oldest one once there are too many
+        match before {
+            Some(before) if self.lines != before.lines => {
+                self.undo.push(before);
+                if self.undo.len() > UNDO_LIMIT {
+                    self.undo.remove(0);
+                }
+                self.redo.clear();
+                self.changed(ctx);
+            },
+            None if joins => {
+                self.redo.clear();
+                self.changed(ctx);
+            },
+            _ => {},
+        }
+        self.typing = typed;
+        true
+    }
+
+    /// Split the lines into the rows that are drawn, for a text `width` cells
+    /// wide. Each row is a `(line, first grapheme, length)` tuple.
+    fn layout(&self, width: usize) -> Vec<(usize, usize, usize)> {
+        let mut rows = Vec::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            if !self.wrap_ || width == 0 {
+                rows.push((i, 0, text::count(line)));
+                continue;
+            }
+
+            // A wide grapheme that doesn't fit at the end of a row starts the
+            // next one, and a line that exactly fills its last row gets an
+            // empty row after it, so that the cursor has somewhere to go at the
+            // end of the line
+            let (mut start, mut length, mut used) = (0, 0, 0);
+            for grapheme in text::graphemes(line) {
+                let grapheme_width = text::grapheme_width(grapheme);
+                if used + grapheme_width > width && length > 0 {
+                    rows.push((i, start, length));
+                    start += length;
+                    (length, used) = (0, 0);
+                }
+                length += 1;
+                used += grapheme_width;
+            }
+            if used >= width && length > 0 {
+                rows.push((i, start, length));
+                (start, length) = (start + length, 0);
+            }
+            rows.push((i, start, length));
+        }
+        rows
+    }
+
+    /// Return the number of graphemes in line `line`
+    fn line_len(&self, line: usize) -> usize {
+        text::count(&self.lines[line])
+    }
+
+    /// Move the cursor as `code` says. With `control`, Left and Right move by
+    /// word and Home and End go to the start and end of the text.
+    fn move_cursor(&mut self, code: KeyCode, control: bool) {
+        let (line, column) = self.cursor;
+        let last = self.lines.len() - 1;
+        let page = (self.text_area().3 as usize).max(1);
+        self.cursor = match code {
+            KeyCode::Left if control => {
+                let graphemes: Vec<&str> = text::graphemes(&self.lines[line]).collect();
+                let mut i = column;
+                while i > 0 && text::is_whitespace(graphemes[i - 1]) { i -= 1; }
+                while i > 0 && !text::is_whitespace(graphemes[i - 1]) { i -= 1; }
+                (line, i)
+            },
+            KeyCode::Left if column > 0 => (line, column - 1),
+            KeyCode::Left if line > 0 => (line - 1, self.line_len(line - 1)),
+            KeyCode::Right if control => {
+                let graphemes: Vec<&str> = text::graphemes(&self.lines[line]).collect();
+                let mut i = column;
+                while i < graphemes.len() && !text::is_whitespace(graphemes[i]) { i += 1; }
+                while i < graphemes.len() && text::is_whitespace(graphemes[i]) { i += 1; }
+                (line, i)
+            },
+            KeyCode::Right if column < self.line_len(line) => (line, column + 1),
+            KeyCode::Right if line < last => (line + 1, 0),
+            KeyCode::Up => (line.saturating_sub(1), column),
+            KeyCode::Down => ((line + 1).min(last), column),
+            KeyCode::PageUp => (line.saturating_sub(page), column),
+            KeyCode::PageDown => ((line + page).min(last), column),
+            KeyCode::Home if control => (0, 0),
+            KeyCode::Home => (line, 0),
+            KeyCode::End if control => (last, self.line_len(last)),
+            KeyCode::End => (line, self.line_len(line)),
+            _ => (line, column),
+        };
+
+        // Moving to a shorter line puts the cursor at its end
+        self.cursor.1 = self.cursor.1.min(self.line_len(self.cursor.0));
+    }
+
+    /// Undo the last edit, or redo the last undone edit if `undo` is `false`
+    fn restore(&mut self, undo: bool, ctx: &mut Context) {
+        let state = if undo { self.undo.pop() } else { self.redo.pop() };
+        if let Some(state) = state {
+            let current = self.state();
+            if undo { self.redo.push(current); } else { self.undo.push(current); }
+            self.cursor = state.cursor;
+            self.lines = state.lines;
+            self.anchor = None;
+            self.typing = false;
+            self.changed(ctx);
+        }
+    }
+
+    /// Return the start and end of the selection, in order, if anything is
+    /// selected
+    fn selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.anchor?;
+        if anchor == self.cursor { return None; }
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    /// Return a copy of the current text and cursor
+    fn state(&self) -> TextAreaState {
+        TextAreaState { cursor: self.cursor, lines: self.lines.clone() }
+    }
+
+    /// Return the area the text itself was last drawn in, inside the border and
+    /// the line numbers, as an `(x, y, width, height)` tuple
+    fn text_area(&self) -> (u16, u16, u16, u16) {
+        let border = if self.border_ { 1 } else { 0 };
+        let gutter = self.gutter_width();
+        let (x, y, width, height) = self.area;
+        (
+            x + border + gutter,
+            y + border,
+            width.saturating_sub(2 * border + gutter),
+            height.saturating_sub(2 * border),
+        )
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the text area has a border. Use when building the text area.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let editor = TextArea::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> TextArea {
+        self.border_ = border;
+        self
+    }
+
+    /// Sets whether line numbers are shown in front of the lines. Use when
+    /// building the text area.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let editor = TextArea::builder()
+    ///     .line_numbers(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn line_numbers(mut self, line_numbers: bool) -> TextArea {
+        self.line_numbers_ = line_numbers;
+        self
+    }
+
+    /// Adds a callback that is run with the new text whenever the text changes.
+    /// Use when building the text area.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let editor = TextArea::builder()
+    ///     .on_change(|ctx, _text| ctx.redraw())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_change(mut self, callback: impl FnMut(&mut Context, &str) + 'static) -> TextArea {
+        self.change_callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Adds a callback that is run when `key` is pressed while the text area has
+    /// the focus, and the text area doesn't use the key for editing. Use when
+    /// building the text area.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// # 
+    /// # fn main() {
+    /// let editor = TextArea::builder()
+    ///     .on_key(KeyCode::Esc, |ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> TextArea {
+        self.callbacks.on_key(key, callback);
+        self
+    }
+
+    /// Sets the text area's theme to `theme`, a [`Theme`]. Use when building the
+    /// text area.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let editor = TextArea::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> TextArea {
+        self.theme_ = theme;
+        self
+    }
+
+    /// Sets the text area's initial text to `text`, a [`String`] with the lines
+    /// separated by `\n`. Use when building the text area.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let editor = TextArea::builder()
+    ///     .text(String::from("first line\nsecond line"))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn text(mut self, text: String) -> TextArea {
+        self.lines = text.split('\n').map(String::from).collect();
+        self.cursor = (0, 0);
+        self
+    }
+
+    /// Sets whether long lines are wrapped at the edge of the text area, instead
+    /// of scrolling it horizontally. Use when building the text area.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let editor = TextArea::builder()
+    ///     .wrap(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn wrap(mut self, wrap: bool) -> TextArea {
+        self.wrap_ = wrap;
+        self
+    }
+}
+impl Buildable for TextArea {
+
+    fn build(self) -> TextArea {
+        TextArea {
+            anchor: self.anchor,
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
+            change_callbacks: self.change_callbacks,
+            colspan: self.colspan,
+            cursor: self.cursor,
+            focused: self.focused,
+            line_numbers_: self.line_numbers_,
+            lines: self.lines,
+            redo: self.redo,
+            rowspan: self.rowspan,
+            scroll: self.scroll,
+            theme_: self.theme_,
+            typing: self.typing,
+            undo: self.undo,
+            wrap_: self.wrap_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> TextArea {
+        TextArea {
+            anchor: None,
+            area: (0, 0, 0, 0),
+            border_: true,
+            callbacks: Callbacks::default(),
+            change_callbacks: Vec::new(),
+            colspan: 1,
+            cursor: (0, 0),
+            focused: false,
+            line_numbers_: false,
+            lines: vec![String::new()],
+            redo: Vec::new(),
+            rowspan: 1,
+            scroll: (0, 0),
+            theme_: default_theme(),
+            typing: false,
+            undo: Vec::new(),
+            wrap_: false,
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> TextArea {
+        TextArea::builder().build()
+    }
+}
+impl Eventable for TextArea {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        match event {
+            Event::Key(key) => self.key(key, ctx) || self.callbacks.handle(event, ctx),
+
+            // Clicking on the text moves the cursor there
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => {
+                let (text_x, text_y, text_width, _) = self.text_area();
+                let rows = self.layout(text_width as usize);
+                let index = self.scroll.0 + row.saturating_sub(text_y) as usize;
+                let offset = column.saturating_sub(text_x) as usize;
+                self.cursor = match rows.get(index) {
+                    Some(&(line, start, len)) => {
+                        let text = &self.lines[line];
+                        let first = if self.wrap_ { start } else { self.scroll.1 };
+                        let end = if self.wrap_ { start + len } else { usize::MAX };
+                        let from = text::byte_index(text, first);
+                        let row = &text[from..text::byte_index(text, end)];
+                        (line, first + text::count(text::clip(row, offset)))
+                    },
+                    None => {
+                        let last = self.lines.len() - 1;
+                        (last, self.line_len(last))
+                    },
+                };
+                self.anchor = None;
+                self.typing = false;
+                self.callbacks.handle(event, ctx);
+                true
+            },
+            _ => self.callbacks.handle(event, ctx),
+        }
+    }
+}
+impl Focusable for TextArea {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for TextArea {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { Some(self) }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+
+        // A size of `0` means the text area was placed with `add()`, so use its
+        // natural size
+        let width = if width == 0 { 40 } else { width };
+        let height = if height == 0 { 10 } else { height };
+        self.area = (x, y, width, height);
+
+        // Create the background box, and the border if there is one, highlighted
+        // if the text area has the focus
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+        if self.border_ {
+            let border_fg = if self.focused { self.theme_.get_focus_rgb() } else { fg };
+            create_border_box(buffer, x, y, width, height, border_fg, bg);
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, bg);
+        }
+
+        // Scroll so that the cursor is always visible
+        let (text_x, text_y, text_width, text_height) = self.text_area();
+        if text_width == 0 || text_height == 0 { return Ok(()); }
+        let rows = self.layout(text_width as usize);
+        let cursor_row = rows
+            .iter()
+            .rposition(|&(line, start, _)| line == self.cursor.0 && start <= self.cursor.1)
+            .unwrap_or(0);
+        let visible_rows = text_height as usize;
+        if cursor_row < self.scroll.0 {
+            self.scroll.0 = cursor_row;
+        }
+        else if cursor_row >= self.scroll.0 + visible_rows {
+            self.scroll.0 = cursor_row + 1 - visible_rows;
+        }
+        if self.wrap_ {
+            self.scroll.1 = 0;
+        }
+        else {
+            let line = &self.lines[self.cursor.0];
+            self.scroll.1 = scroll_to(line, self.scroll.1, self.cursor.1, text_width as usize);
+        }
+
+        // Create the visible rows, with their line numbers and the selection
+        let selection = self.selection();
+        let gutter = self.gutter_width();
+        let visible_range = rows.iter().enumerate().skip(self.scroll.0).take(visible_rows);
+        for (i, &(line, start, len)) in visible_range {
+            let row_y = text_y + (i - self.scroll.0) as u16;
+            if self.line_numbers_ && start == 0 {
+                let number = format!("{:>1$}", line + 1, gutter as usize - 1);
+                buffer.set_string(text_x - gutter, row_y, &number, fg, bg);
+                buffer.set_attribute(text_x - gutter, row_y, gutter - 1, Attribute::Dim);
+            }
+            let text = &self.lines[line];
+            let first = if self.wrap_ { start } else { self.scroll.1 };
+            let end = if self.wrap_ { text::byte_index(text, start + len) } else { text.len() };
+            let from = text::byte_index(text, first);
+            let visible = text::clip(&text[from..end], text_width as usize);
+            buffer.set_string(text_x, row_y, visible, fg, bg);
+            if let Some((from, to)) = selection {
+                let mut column = text_x;
+                for (j, grapheme) in text::graphemes(visible).enumerate() {
+                    let grapheme_width = text::grapheme_width(grapheme) as u16;
+                    let position = (line, first + j);
+                    if from <= position && position < to {
+                        buffer.set_attribute(column, row_y, grapheme_width, Attribute::Reverse);
+                    }
+                    column += grapheme_width;
+                }
+            }
+        }
+
+        // Show the cursor as a reversed cell
+        if self.focused {
+            let line = &self.lines[self.cursor.0];
+            let first = if self.wrap_ { rows[cursor_row].1 } else { self.scroll.1 };
+            let from = text::byte_index(line, first);
+            let to = text::byte_index(line, self.cursor.1);
+            let cursor_x = text_x + text::width(&line[from..to]) as u16;
+            let cursor_y = text_y + (cursor_row - self.scroll.0) as u16;
+            let next = text::byte_index(line, self.cursor.1 + 1);
+            let cursor_width = text::width(&line[to..next]).max(1) as u16;
+            buffer.set_attribute(cursor_x, cursor_y, cursor_width, Attribute::Reverse);
+        }
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A copy of the text and cursor of a [`TextArea`], kept for undo and redo
+struct TextAreaState {
+    /// The position of the cursor, as a `(line, char)` tuple
+    cursor: (usize, usize),
+    /// The lines of text
+    lines: Vec<String>,
+}
+
+/// A single-line text input. It has a cursor that can be moved with the arrow
+/// keys, Home and End, and Ctrl+Left/Right for whole words, and scrolls
+/// horizontally when the text is wider than the input.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create a password input
+///     let input = TextInput::builder()
+///         .placeholder(String::from("Password"))
+///         .password(true)
+///         .max_length(32)
+///         .build();
+///     window.grid(Box::new(input), 1, 1, 1, 1);
+///     // window.run();
+/// }
+/// ```
+pub struct TextInput {
+    /// The area the input was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the input has a border
+    border_: bool,
+    /// The callbacks run when a key is pressed or the input is clicked
+    callbacks: Callbacks,
+    /// The callbacks run when the value changes
+    change_callbacks: Vec<ChangeCallback<str>>,
+    /// The number of grid columns this input spans
+    colspan: u16,
+    /// The position of the cursor, in chars from the start of the value
+    cursor: usize,
+    /// Whether the input has the focus
+    focused: bool,
+    /// The maximum number of chars in the value, if there is one
+    max_length_: Option<usize>,
+    /// Whether the value is hidden behind `*`s
+    password_: bool,
+    /// The text shown when the value is empty
+    placeholder_: String,
+    /// The number of grid rows this input spans
+    rowspan: u16,
+    /// The index of the first grapheme of the value that is visible
+    scroll: usize,
+    /// The [`Theme`] that this input uses for its colors
+    theme_: Theme,
+    /// The text typed into the input
+    value_: String,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl TextInput {
+
+    /// Return the text typed into the input
+    pub fn get_value(&self) -> &str {
+        &self.value_
+    }
+
+    /// Return the number of graphemes in the value
+    fn len(&self) -> usize {
+        text::count(&self.value_)
+    }
+
+    /// Return the value as it is shown, with one `*` per grapheme for a password
+    fn shown(&self) -> String {
+        if self.password_ { "*".repeat(self.len()) } else { self.value_.clone() }
+    }
+
+    /// Return the position of the first grapheme of the word before the cursor
+    fn word_left(&self) -> usize {
+        let graphemes: Vec<&str> = text::graphemes(&self.value_).collect();
+        let mut i = self.cursor;
+        while i > 0 && text::is_whitespace(graphemes[i - 1]) { i -= 1; }
+        while i > 0 && !text::is_whitespace(graphemes[i - 1]) { i -= 1; }
+        i
+    }
+
+    /// Return the position of the first grapheme of the word after the cursor
+    fn word_right(&self) -> usize {
+        let graphemes: Vec<&str> = text::graphemes(&self.value_).collect();
+        let mut i = self.cursor;
+        while i < graphemes.len() && !text::is_whitespace(graphemes[i]) { i += 1; }
+        while i < graphemes.len() && text::is_whitespace(graphemes[i]) { i += 1; }
+        i
+    }
+
+    /// Run all the on-change callbacks with the current value
+    fn changed(&mut self, ctx: &mut Context) {
+        for callback in &mut self.change_callbacks {
+            callback(ctx, &self.value_);
+        }
+    }
+
+    /// Handle a key press, editing the value or moving the cursor. Returns whether
+    /// the key was handled.
+    fn key(&mut self, key: KeyEvent, ctx: &mut Context) -> bool {
+        let word = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char(c) if !word && !key.modifiers.contains(KeyModifiers::ALT) => {
+                if self.max_length_.is_some_and(|max| self.len() >= max) {
+                    return true;
+                }
+                let i = text::byte_index(&self.value_, self.cursor);
+                self.value_.insert(i, c);
+
+                // A combining char joins the grapheme before it instead of
+                // making a new one
+                self.cursor = text::count(&self.value_[..i + c.len_utf8()]);
+                self.changed(ctx);
+            },
+            KeyCode::Backspace => {
+                if self.cursor > 0 {
+                    let i = text::byte_index(&self.value_, self.cursor - 1);
+                    let j = text::byte_index(&self.value_, self.cursor);
+                    self.value_.replace_range(i..j, "");
+                    self.cursor -= 1;
+                    self.changed(ctx);
+                }
+            },
+            KeyCode::Delete => {
+                if self.cursor < self.len() {
+                    let i = text::byte_index(&self.value_, self.cursor);
+                    let j = text::byte_index(&self.value_, self.cursor + 1);
+                    self.value_.replace_range(i..j, "");
+                    self.changed(ctx);
+                }
+            },
+            KeyCode::Left if word => self.cursor = self.word_left(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right if word => self.cursor = self.word_right(),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the input has a border. Use when building the input.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> TextInput {
+        self.border_ = border;
+        self
+    }
+
+    /// Sets the maximum number of chars that can be typed into the input. Use
+    /// when building the input.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .max_length(8)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn max_length(mut self, max_length: usize) -> TextInput {
+        self.max_length_ = Some(max_length);
+        self
+    }
+
+    /// Adds a callback that is run with the new value whenever the value changes.
+    /// Use when building the input.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .on_change(|_ctx, value| assert!(value.len() < 100))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_change(mut self, callback: impl FnMut(&mut Context, &str) + 'static) -> TextInput {
+        self.change_callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Adds a callback that is run when `key` is pressed while the input has the
+    /// focus, and the input doesn't use the key for editing. Use when building the
+    /// input.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .on_key(KeyCode::Enter, |ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> TextInput {
+        self.callbacks.on_key(key, callback);
+        self
+    }
+
+    /// Sets whether the value is hidden behind `*`s, e.g. for passwords. Use when
+    /// building the input.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .password(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn password(mut self, password: bool) -> TextInput {
+        self.password_ = password;
+        self
+    }
+
+    /// Sets the text shown, dimmed, while the input is empty. Use when building
+    /// the input.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .placeholder(String::from("Search..."))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn placeholder(mut self, placeholder: String) -> TextInput {
+        self.placeholder_ = placeholder;
+        self
+    }
+
+    /// Sets the input's theme to `theme`, a [`Theme`]. Use when building the input.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> TextInput {
+        self.theme_ = theme;
+        self
+    }
+
+    /// Sets the input's initial value to `value`, a [`String`], placing the cursor
+    /// at its end. Use when building the input.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let input = TextInput::builder()
+    ///     .value(String::from("localhost"))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn value(mut self, value: String) -> TextInput {
+        self.value_ = value;
+        self.cursor = self.len();
+        self
+    }
+}
+impl Buildable for TextInput {
+
+    fn build(self) -> TextInput {
+        TextInput {
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
+            change_callbacks: self.change_callbacks,
+            colspan: self.colspan,
+            cursor: self.cursor,
+            focused: self.focused,
+            max_length_: self.max_length_,
+            password_: self.password_,
+            placeholder_: self.placeholder_,
+            rowspan: self.rowspan,
+            scroll: self.scroll,
+            theme_: self.theme_,
+            value_: self.value_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> TextInput {
+        TextInput {
+            area: (0, 0, 0, 0),
+            border_: true,
+            callbacks: Callbacks::default(),
+            change_callbacks: Vec::new(),
+            colspan: 1,
+            cursor: 0,
+            focused: false,
+            max_length_: None,
+            password_: false,
+            placeholder_: String::from(""),
+            rowspan: 1,
+            scroll: 0,
+            theme_: default_theme(),
+            value_: String::from(""),
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> TextInput {
+        TextInput::builder().build()
+    }
+}
+impl Eventable for TextInput {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        match event {
+            Event::Key(key) => self.key(key, ctx) || self.callbacks.handle(event, ctx),
+
+            // Clicking on the text moves the cursor there
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                ..
+            }) => {
+                let text_x = self.area.0 + if self.border_ { 1 } else { 0 };
+                let offset = column.saturating_sub(text_x) as usize;
+                let shown = self.shown();
+                let first = text::byte_index(&shown, self.scroll);
+                self.cursor = self.scroll + text::count(text::clip(&shown[first..], offset));
+                self.callbacks.handle(event, ctx);
+                true
+            },
+            _ => self.callbacks.handle(event, ctx),
+        }
+    }
+}
+impl Focusable for TextInput {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for TextInput {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { Some(self) }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+
+        // A size of `0` means the input was placed with `add()`, so use its
+        // natural size
+        let border = if self.border_ { 1 } else { 0 };
+        let width = if width == 0 { 20 } else { width };
+        let height = if height == 0 { 1 + 2 * border } else { height };
+        self.area = (x, y, width, height);
+
+        // Create the background box, and the border if there is one, highlighted
+        // if the input has the focus
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+        if self.border_ {
+            let border_fg = if self.focused { self.theme_.get_focus_rgb() } else { fg };
+            create_border_box(buffer, x, y, width, height, border_fg, bg);
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, bg);
+        }
+
+        // Scroll so that the cursor is always visible; it can sit just past the
+        // last grapheme
+        let text_x = x + border;
+        let text_y = y + border;
+        let text_width = width.saturating_sub(2 * border) as usize;
+        if text_width == 0 { return Ok(()); }
+        let shown = self.shown();
+        self.scroll = scroll_to(&shown, self.scroll, self.cursor, text_width);
+        let first = text::byte_index(&shown, self.scroll);
+
+        // Create the text, or the placeholder if there isn't any
+        if self.value_.is_empty() {
+            let placeholder = text::clip(&self.placeholder_, text_width);
+            buffer.set_string(text_x, text_y, placeholder, fg, bg);
+            let placeholder_width = text::width(placeholder) as u16;
+            buffer.set_attribute(text_x, text_y, placeholder_width, Attribute::Dim);
+        }
+        else {
+            let visible = text::clip(&shown[first..], text_width);
+            buffer.set_string(text_x, text_y, visible, fg, bg);
+        }
+
+        // Show the cursor as a reversed cell, as wide as the grapheme under it
+        if self.focused {
+            let cursor = text::byte_index(&shown, self.cursor);
+            let cursor_x = text_x + text::width(&shown[first..cursor]) as u16;
+            let next = text::byte_index(&shown, self.cursor + 1);
+            let cursor_width = text::width(&shown[cursor..next]).max(1) as u16;
+            buffer.set_attribute(cursor_x, text_y, cursor_width, Attribute::Reverse);
+        }
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// The type of the callbacks that load the children of a tree node when it is
+/// first expanded. They receive the text of every node on the way to it, from
+/// the root down to the node itself.
+pub type LoadCallback = Box<dyn FnMut(&mut Context, &[String]) -> Vec<TreeNode>>;
+
+/// A tree of expandable and collapsible [`TreeNode`]s, drawn with guide lines
+/// from the parents to their children. Up and Down move the selection, Left
+/// collapses the selected node or moves to its parent, Right expands it or moves
+/// to its first child, and Enter or Space toggles it.
+/// 
+/// Children can be loaded lazily: a node built with [`TreeNode::lazy()`] asks
+/// the tree's [`Tree::on_load()`] callback for its children when it is first
+/// expanded.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::*;
+/// 
+/// fn main() {
+/// 
+///     // Create the window
+///     let mut window = Window::headless(80, 24);
+/// 
+///     // Create the tree, loading the contents of each directory when it is
+///     // opened
+///     let tree = Tree::builder()
+///         .node(TreeNode::builder().text(String::from("/")).lazy(true).build())
+///         .on_load(|_ctx, path| {
+///             let dir = path.join("/");
+///             vec![TreeNode::builder().text(format!("{}/file", dir)).build()]
+///         })
+///         .build();
+///     window.grid(Box::new(tree), 1, 1, 3, 2);
+///     // window.run();
+/// }
+/// ```
+pub struct Tree {
+    /// The area the tree was last drawn in, as an `(x, y, width, height)` tuple
+    area: (u16, u16, u16, u16),
+    /// Whether the tree has a border
+    border_: bool,
+    /// The callbacks run when a key is pressed or the tree is clicked
+    callbacks: Callbacks,
+    /// The number of grid columns this tree spans
+    colspan: u16,
+    /// Whether the tree has the focus
+    focused: bool,
+    /// The callback that loads the children of lazy nodes, if there is one
+    loader: Option<LoadCallback>,
+    /// The top level nodes of the tree
+    nodes: Vec<TreeNode>,
+    /// The number of grid rows this tree spans
+    rowspan: u16,
+    /// The index of the first visible row
+    scroll: usize,
+    /// The callbacks run when the selected node changes
+    select_callbacks: Vec<ChangeCallback<[String]>>,
+    /// The indices of the nodes on the way to the selected node
+    selected: Vec<usize>,
+    /// The [`Theme`] that this tree uses for its colors
+    theme_: Theme,
+    /// The x position of this child, in either characters or grid units
+    x: u16,
+    /// The y position of this child, in either characters or grid units
+    y: u16,
+}
+impl Tree {
+
+    /// Return the node at `path`, a list of child indices starting at the top
+    /// level, if there is one
+    pub fn get_node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.nodes.get(*first)?, |node, i| node.children.get(*i))
+    }
+
+    /// Return the text of every node on the way to the selected node, from the
+    /// top level down to the selected node itself
+    pub fn get_selected(&self) -> Vec<String> {
+        self.texts(&self.selected)
+    }
+
+    /// Collapse or expand the node at `path`, loading its children first if it is
+    /// lazy and they haven't been loaded yet
+    fn set_expanded(&mut self, path: &[usize], expanded: bool, ctx: &mut Context) {
+        let load = expanded && self.get_node(path).is_some_and(|node| node.lazy_ && !node.loaded);
+        if load {
+            let texts = self.texts(path);
+            let children = match &mut self.loader {
+                Some(loader) => loader(ctx, &texts),
+                None => Vec::new(),
+            };
+            if let Some(node) = self.node_mut(path) {
+                node.children = children;
+                node.loaded = true;
+            }
+        }
+        if let Some(node) = self.node_mut(path) {
+            node.expanded_ = expanded && node.has_children();
+        }
+    }
+
+    /// Return the node at `path` mutably, if there is one
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.nodes.get_mut(*first)?, |node, i| node.children.get_mut(*i))
+    }
+
+    /// Return the number of rows that fit in the tree
+    fn page_size(&self) -> usize {
+        let border = if self.border_ { 2 } else { 0 };
+        (self.area.3.saturating_sub(border) as usize).max(1)
+    }
+
+    /// Return every visible row of the tree, from top to bottom, as a tuple of
+    /// the path to its node and the guide lines in front of it
+    fn rows(&self) -> Vec<(Vec<usize>, String)> {
+        let mut rows = Vec::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            rows.push((vec![i], String::new()));
+            tree_rows(node, vec![i], String::new(), &mut rows);
+        }
+        rows
+    }
+
+    /// Scroll the tree by `rows` rows, up if negative, without moving past either
+    /// end
+    fn scroll_by(&mut self, rows: isize) {
+        let max = self.rows().len().saturating_sub(self.page_size());
+        self.scroll = self.scroll.saturating_add_signed(rows).min(max);
+    }
+
+    /// Select the node at `path`, scrolling it into view, and run the on-select
+    /// callbacks if it changed
+    fn select(&mut self, path: Vec<usize>, ctx: &mut Context) {
+        let row = self.rows().iter().position(|(row, _)| *row == path).unwrap_or(0);
+        let page = self.page_size();
+        if row < self.scroll {
+            self.scroll = row;
+        }
+        else if row >= self.scroll + page {
+            self.scroll = row + 1 - page;
+        }
+        if path != self.selected {
+            self.selected = path;
+            let texts = self.get_selected();
+            for callback in &mut self.select_callbacks {
+                callback(ctx, &texts);
+            }
+        }
+    }
+
+    /// Select the visible row `offset` rows away from the selected one, stopping
+    /// at the first and last rows
+    fn select_offset(&mut self, offset: isize, ctx: &mut Context) {
+        let rows = self.rows();
+        if rows.is_empty() { return; }
+        let row = rows.iter().position(|(row, _)| *row == self.selected).unwrap_or(0);
+        let row = row.saturating_add_signed(offset).min(rows.len() - 1);
+        self.select(rows[row].0.clone(), ctx);
+    }
+
+    /// Return the text of every node on the way to the node at `path`
+    fn texts(&self, path: &[usize]) -> Vec<String> {
+        (1..=path.len())
+            .filter_map(|length| self.get_node(&path[..length]))
+            .map(|node| node.text_.clone())
+            .collect()
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Sets whether the tree has a border. Use when building the tree.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tree = Tree::builder()
+    ///     .border(false)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn border(mut self, border: bool) -> Tree {
+        self.border_ = border;
+        self
+    }
+
+    /// Adds `node`, a [`TreeNode`], to the top level of the tree. Use when
+    /// building the tree.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tree = Tree::builder()
+    ///     .node(TreeNode::builder()
+    ///         .text(String::from("config"))
+    ///         .child(TreeNode::builder().text(String::from("port")).build())
+    ///         .build())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn node(mut self, node: TreeNode) -> Tree {
+        self.nodes.push(node);
+        self.selected = vec![0];
+        self
+    }
+
+    /// Adds a callback that is run when `key` is pressed while the tree has the
+    /// focus, and the tree doesn't use the key itself. Use when building the tree.
+    /// 
+    /// `key` is a [`KeyCode`], or a [`KeyEvent`] to require modifiers too.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use crossterm::event::KeyCode;
+    /// # 
+    /// # fn main() {
+    /// let tree = Tree::builder()
+    ///     .on_key(KeyCode::Esc, |ctx| ctx.quit())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_key(
+        mut self,
+        key: impl Into<KeyEvent>,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> Tree {
+        self.callbacks.on_key(key, callback);
+        self
+    }
+
+    /// Sets the callback that returns the children of a lazy node when it is
+    /// first expanded. It receives the text of every node on the way to that
+    /// node. Use when building the tree.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tree = Tree::builder()
+    ///     .on_load(|_ctx, path| {
+    ///         vec![TreeNode::builder().text(format!("child of {}", path.join("."))).build()]
+    ///     })
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_load(
+        mut self,
+        callback: impl FnMut(&mut Context, &[String]) -> Vec<TreeNode> + 'static
+    ) -> Tree {
+        self.loader = Some(Box::new(callback));
+        self
+    }
+
+    /// Adds a callback that is run whenever a node is selected. It receives the
+    /// text of every node on the way to the selected node. Use when building the
+    /// tree.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tree = Tree::builder()
+    ///     .on_select(|_ctx, path| assert!(!path.is_empty()))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_select(mut self, callback: impl FnMut(&mut Context, &[String]) + 'static) -> Tree {
+        self.select_callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Sets the tree's theme to `theme`, a [`Theme`]. Use when building the tree.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let tree = Tree::builder()
+    ///     .set_theme(default_theme())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn set_theme(mut self, theme: Theme) -> Tree {
+        self.theme_ = theme;
+        self
+    }
+}
+impl Buildable for Tree {
+
+    fn build(self) -> Tree {
+        Tree {
+            area: self.area,
+            border_: self.border_,
+            callbacks: self.callbacks,
+            colspan: self.colspan,
+            focused: self.focused,
+            loader: self.loader,
+            nodes: self.nodes,
+            rowspan: self.rowspan,
+            scroll: self.scroll,
+            select_callbacks: self.select_callbacks,
+            selected: self.selected,
+            theme_: self.theme_,
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    fn builder() -> Tree {
+        Tree {
+            area: (0, 0, 0, 0),
+            border_: true,
+            callbacks: Callbacks::default(),
+            colspan: 1,
+            focused: false,
+            loader: None,
+            nodes: Vec::new(),
+            rowspan: 1,
+            scroll: 0,
+            select_callbacks: Vec::new(),
+            selected: Vec::new(),
+            theme_: default_theme(),
+            x: 1,
+            y: 1,
+        }
+    }
+
+    fn new() -> Tree {
+        Tree::builder().build()
+    }
+}
+impl Eventable for Tree {
+    fn event_send(&mut self, event: Event, ctx: &mut Context) -> bool {
+        let page = self.page_size() as isize;
+        let selected = self.selected.clone();
+        let expanded = self.get_node(&selected).is_some_and(|node| node.expanded_);
+        let expandable = self.get_node(&selected).is_some_and(|node| node.has_children());
+        match event {
+            Event::Key(key) => match key.code {
+                KeyCode::Up => self.select_offset(-1, ctx),
+                KeyCode::Down => self.select_offset(1, ctx),
+                KeyCode::PageUp => self.select_offset(-page, ctx),
+                KeyCode::PageDown => self.select_offset(page, ctx),
+                KeyCode::Home => self.select_offset(isize::MIN, ctx),
+                KeyCode::End => self.select_offset(isize::MAX, ctx),
+
+                // Left collapses an expanded node, or else moves to its parent
+                KeyCode::Left if expanded => self.set_expanded(&selected, false, ctx),
+                KeyCode::Left if selected.len() > 1 => {
+                    self.select(selected[..selected.len() - 1].to_vec(), ctx);
+                },
+
+                // Right expands a collapsed node, or else moves to its first child
+                KeyCode::Right if expanded => self.select_offset(1, ctx),
+                KeyCode::Right if expandable => self.set_expanded(&selected, true, ctx),
+                KeyCode::Enter | KeyCode::Char(' ') if expandable => {
+                    self.set_expanded(&selected, !expanded, ctx);
+                },
+                KeyCode::Left | KeyCode::Right => {},
+                _ => return self.callbacks.handle(event, ctx),
+            },
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollUp => self.scroll_by(-1),
+                MouseEventKind::ScrollDown => self.scroll_by(1),
+
+                // Clicking on a node selects it, and clicking on its arrow
+                // toggles it
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let border = if self.border_ { 1 } else { 0 };
+                    let (left, top) = (self.area.0 + border, self.area.1 + border);
+                    let right = (self.area.0 + self.area.2).saturating_sub(border);
+                    let offset = mouse.row.saturating_sub(top) as usize;
+                    let visible = mouse.column >= left
+                        && mouse.column < right
+                        && mouse.row >= top
+                        && offset < self.page_size();
+                    let row = self.scroll + offset;
+                    let clicked = if visible { self.rows().get(row).cloned() } else { None };
+                    if let Some((path, guides)) = clicked {
+                        let arrow_x = self.area.0 + border + text::width(&guides) as u16;
+                        if mouse.column == arrow_x {
+                            let expanded = self.get_node(&path).is_some_and(|node| node.expanded_);
+                            self.set_expanded(&path, !expanded, ctx);
+                        }
+                        self.select(path, ctx);
+                    }
+                    self.callbacks.handle(event, ctx);
+                },
+                _ => return self.callbacks.handle(event, ctx),
+            },
+            _ => return self.callbacks.handle(event, ctx),
+        }
+        true
+    }
+}
+impl Focusable for Tree {
+    fn focus_add(&mut self) { self.focused = true; }
+    fn focus_remove(&mut self) { self.focused = false; }
+}
+impl Widget for Tree {
+
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+
+    fn as_focusable(&mut self) -> Option<&mut dyn Focusable> { Some(self) }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+
+        // A size of `0` means the tree was placed with `add()`, so use its
+        // natural size
+        let border = if self.border_ { 1 } else { 0 };
+        let rows = self.rows();
+        let width = if width == 0 { 30 } else { width };
+        let height = if height == 0 { rows.len().min(10) as u16 + 2 * border } else { height };
+        self.area = (x, y, width, height);
+
+        // Create the background box, and the border if there is one, highlighted
+        // if the tree has the focus
+        let fg = self.theme_.get_fg_rgb();
+        let bg = self.theme_.get_bg_rgb();
+        if self.border_ {
+            let border_fg = if self.focused { self.theme_.get_focus_rgb() } else { fg };
+            create_border_box(buffer, x, y, width, height, border_fg, bg);
+        }
+        else {
+            create_fill_box(buffer, x, y, width, height, bg);
+        }
+
+        // The tree may have gotten taller since it was last scrolled
+        self.scroll_by(0);
+
+        // Create the visible rows: the guide lines, an arrow for nodes with
+        // children, and the text, highlighting the selected node
+        let text_width = width.saturating_sub(2 * border);
+        let visible = rows.iter().skip(self.scroll).take(self.page_size());
+        for (row, (path, guides)) in visible.enumerate() {
+            let node = match self.get_node(path) {
+                Some(node) => node,
+                None => continue,
+            };
+            let arrow = if !node.has_children() {
+                " "
+            }
+            else if node.expanded_ {
+                chars::TREE_EXPANDED
+            }
+            else {
+                chars::TREE_COLLAPSED
+            };
+            let row_x = x + border;
+            let row_y = y + border + row as u16;
+            let text = format!("{}{} {}", guides, arrow, node.text_);
+            let text = text::truncate(&text, text_width as usize);
+            let row_bg = if *path == self.selected { self.theme_.get_selected_rgb() } else { bg };
+            create_fill_box(buffer, row_x, row_y, text_width, 1, row_bg);
+            buffer.set_string(row_x, row_y, &text, fg, row_bg);
+            buffer.set_attribute(row_x, row_y, text::width(guides) as u16, Attribute::Dim);
+        }
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (self.rowspan, self.colspan) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, rowspan: u16, colspan: u16) {
+        self.rowspan = rowspan;
+        self.colspan = colspan;
+    }
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// A node of a [`Tree`]: a line of text, and optionally child nodes below it.
+pub struct TreeNode {
+    /// The child nodes of this node
+    children: Vec<TreeNode>,
+    /// Whether the children of this node are shown
+    expanded_: bool,
+    /// Whether the children of this node are loaded by the tree when it is first
+    /// expanded
+    lazy_: bool,
+    /// Whether the children of this lazy node have been loaded
+    loaded: bool,
+    /// The text shown for this node
+    text_: String,
+}
+impl TreeNode {
+
+    /// Return the child nodes of this node
+    pub fn get_children(&self) -> &[TreeNode] {
+        &self.children
+    }
+
+    /// Return the text shown for this node
+    pub fn get_text(&self) -> &str {
+        &self.text_
+    }
+
+    /// Return whether this node has children, or may have children that haven't
+    /// been loaded yet
+    pub fn has_children(&self) -> bool {
+        !self.children.is_empty() || (self.lazy_ && !self.loaded)
+    }
+
+    /// Return whether the children of this node are shown
+    pub fn is_expanded(&self) -> bool {
+        self.expanded_
+    }
+
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Adds `child`, a [`TreeNode`], below this node. Use when building the node.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let node = TreeNode::builder()
+    ///     .text(String::from("src"))
+    ///     .child(TreeNode::builder().text(String::from("lib.rs")).build())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn child(mut self, child: TreeNode) -> TreeNode {
+        self.children.push(child);
+        self
+    }
+
+    /// Sets whether the children of this node are shown at first. Use when
+    /// building the node.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let node = TreeNode::builder()
+    ///     .expanded(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn expanded(mut self, expanded: bool) -> TreeNode {
+        self.expanded_ = expanded;
+        self
+    }
+
+    /// Sets whether the children of this node are loaded with the tree's
+    /// [`Tree::on_load()`] callback when it is first expanded. Use when building
+    /// the node.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let node = TreeNode::builder()
+    ///     .lazy(true)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn lazy(mut self, lazy: bool) -> TreeNode {
+        self.lazy_ = lazy;
+        self
+    }
+
+    /// Sets the text shown for this node to `text`, a [`String`]. Use when
+    /// building the node.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let node = TreeNode::builder()
+    ///     .text(String::from("README.md"))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn text(mut self, text: String) -> TreeNode {
+        self.text_ = text;
+        self
+    }
+}
+impl Buildable for TreeNode {
+
+    fn build(self) -> TreeNode {
+        TreeNode {
+            children: self.children,
+            expanded_: self.expanded_,
+            lazy_: self.lazy_,
+            loaded: self.loaded,
+            text_: self.text_,
+        }
+    }
+
+    fn builder() -> TreeNode {
+        TreeNode {
+            children: Vec::new(),
+            expanded_: false,
+            lazy_: false,
+            loaded: false,
+            text_: String::from(""),
+        }
+    }
+
+    fn new() -> TreeNode {
+        TreeNode::builder().build()
+    }
+}
+
+/// Add a row for every visible child of `node`, found at `path`, to `rows`. Each
+/// row has the path to its node and the guide lines in front of it; `guides` are
+/// the guide lines of `node`'s own ancestors.
+fn tree_rows(
+    node: &TreeNode,
+    path: Vec<usize>,
+    guides: String,
+    rows: &mut Vec<(Vec<usize>, String)>
+) {
+    if !node.expanded_ { return; }
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        let mut child_path = path.clone();
+        child_path.push(i);
+        let branch = if last { chars::TREE_LAST_BRANCH } else { chars::TREE_BRANCH };
+        rows.push((child_path.clone(), format!("{}{}", guides, branch)));
+        let guide = if last { chars::TREE_EMPTY } else { chars::TREE_GUIDE };
+        tree_rows(child, child_path, format!("{}{}", guides, guide), rows);
+    }
+}
+
+/// The type of the handlers that run when a window picks up a message posted
+/// with [`Sender::send()`]. A handler gives the message back if it isn't of the
+/// type that it handles.
+type MessageHandler = Box<
+    dyn FnMut(&mut Window, Box<dyn Any + Send>) -> std::result::Result<(), Box<dyn Any + Send>>
+>;
+
+/// Something posted to a window through a [`Sender`]
+enum Message {
+    /// A closure that is run with the window
+    Call(Box<dyn FnOnce(&mut Window) + Send>),
+    /// A custom message that is handed to the window's message handlers
+    User(Box<dyn Any + Send>),
+}
+
+/// A handle for posting messages and closures to a running window from other
+/// threads, obtained with [`Window::sender()`]. Senders can be cloned, and
+/// sent to other threads.
+/// 
+/// The window picks up everything that was posted while it runs, and redraws
+/// afterwards.
+/// 
+/// Example:
+/// 
+/// ```
+/// use rusty_panther::prelude::*;
+/// use rusty_panther::widgets::Window;
+/// 
+/// use std::thread;
+/// 
+/// fn main() {
+///     let mut window = Window::builder()
+///         .backend(rusty_panther::backend::MemoryBackend::new(80, 24))
+///         .on_message(|window: &mut Window, lines: usize| {
+///             *window.get_state::<usize>().unwrap() += lines;
+///         })
+///         .state(0usize)
+///         .build();
+/// 
+///     // Count lines on another thread, and hand the result to the window
+///     let sender = window.sender();
+///     thread::spawn(move || sender.send(42usize)).join().unwrap();
+///     window.update(std::time::Instant::now());
+///     assert_eq!(*window.get_state::<usize>().unwrap(), 42);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Sender {
+    /// The sending half of the window's channel
+    sender: mpsc::Sender<Message>,
+}
+impl Sender {
+
+    /// Create a new sender posting to the window that has `sender`'s receiver
+    fn new(sender: mpsc::Sender<Message>) -> Sender {
+        Sender { sender }
+    }
+
+    /// Post `callback` to the window, which runs it with itself, e.g. to change
+    /// its widgets or its state. Returns [`false`] if the window is gone.
+    pub fn call(&self, callback: impl FnOnce(&mut Window) + Send + 'static) -> bool {
+        self.sender.send(Message::Call(Box::new(callback))).is_ok()
+    }
+
+    /// Post `message` to the window, which hands it to the handler added for its
+    /// type with [`Window::on_message()`]. Returns [`false`] if the window is gone.
+    pub fn send<M: Any + Send>(&self, message: M) -> bool {
+        self.sender.send(Message::User(Box::new(message))).is_ok()
+    }
+}
+
+/// The main window for the terminal application; this contains all the widgets.
+/// 
+/// Example:
+/// 
+/// ```no_run
+/// use rusty_panther::prelude::*;
+/// 
+/// fn main() {
+///     
+///     // The window
+///     let mut window = widgets::Window::new();
+///     // window.run();
+/// # window.quit()
+/// }
+/// ```
+pub struct Window {
+    /// The children added with [`Parent::add()`], placed in characters
+    absolute_children: Children,
+    /// The [`Backend`] that every frame is written to
+    backend: BackendGuard,
+    /// The [`Buffer`] that the children are drawn into for the next frame
+    buffer: Buffer,
+    /// All the immediate children of this widget (e.g., excludes grandchildren, 
+    /// great-grandchildren, etc.)
+    children: Children,
+    /// The first error that came up where it couldn't be returned, e.g. while
+    /// redrawing after an event; [`Window::run()`] returns it, and
+    /// [`Window::take_error()`] hands it out
+    error: Option<Error>,
+    /// The path of child indices leading to the widget that has the focus, and
+    /// gets all the key events; empty if no widget has the focus
+    focus: Vec<usize>,
+    /// The [`Grid`] that manages all of the widget-sizing calculations
+    grid: Grid,
+    /// The path of child indices leading to the innermost widget under the mouse
+    /// pointer; empty if there is none
+    hover: Vec<usize>,
+    /// Whether a [`Sender`] was handed out, so that the event listener has to
+    /// keep checking for messages
+    listening: bool,
+    /// The handlers that the messages posted with [`Sender::send()`] are handed to
+    message_handlers: Vec<MessageHandler>,
+    /// The receiving half of the channel that [`Sender`]s post to
+    messages: mpsc::Receiver<Message>,
+    /// When the next tick is due, if the window ticks
+    next_tick: Option<Instant>,
+    /// Whether to install a panic hook that restores the terminal
+    panic_hook_: bool,
+    /// The [`Buffer`] holding the frame that is currently on the screen; used
+    /// to find out which cells need to be rewritten
+    previous: Buffer,
+    /// Whether a callback asked the window to quit
+    quitting: bool,
+    /// The height of the terminal screen, as of the last frame
+    screen_height: u16,
+    /// The width of the terminal screen, as of the last frame
+    screen_width: u16,
+    /// The sending half of the channel that [`Sender`]s post to
+    sender_: mpsc::Sender<Message>,
+    /// The application state that callbacks can access through their [`Context`]
+    state_: Option<Box<dyn Any>>,
+    /// The [`Theme`] that the window uses.
+    theme_: Theme,
+    /// How often the widgets get a tick, if they do
+    tick_rate_: Option<Duration>,
+    /// The one-shot and repeating timers added to the window
+    timers: Timers,
+}
+impl Window {
+
+    /// Returns the frame that is currently on the screen. Each [`Cell`] holds the
+    /// text and the style of one character of the screen.
+    pub fn screen(&self) -> &Buffer {
+        &self.previous
+    }
+
+    /// Runs `callback` every `period`, starting `period` from now, until the
+    /// timer is cancelled with [`Window::cancel_timer()`]. A `period` shorter than
+    /// [`MIN_PERIOD`](crate::events::MIN_PERIOD), e.g. zero, is raised to it.
+    /// Returns the ID of the timer.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use std::time::Duration;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// window.add_interval(Duration::from_secs(1), |ctx| ctx.redraw());
+    /// # }
+    /// ```
+    pub fn add_interval(
+        &mut self,
+        period: Duration,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> TimerId {
+        self.timers.add(Instant::now(), period, Some(period), Box::new(callback))
+    }
+
+    /// Runs `callback` once, `delay` from now. Returns the ID of the timer, which
+    /// can be used to cancel it with [`Window::cancel_timer()`].
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use std::time::Duration;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// window.add_timer(Duration::from_secs(30), |ctx| ctx.quit());
+    /// # }
+    /// ```
+    pub fn add_timer(
+        &mut self,
+        delay: Duration,
+        callback: impl FnMut(&mut Context) + 'static
+    ) -> TimerId {
+        self.timers.add(Instant::now(), delay, None, Box::new(callback))
+    }
+
+    /// Cancels the timer with the ID `id`. Returns whether there was such a timer.
+    pub fn cancel_timer(&mut self, id: TimerId) -> bool {
+        self.timers.cancel(id)
+    }
+
+    /// Returns all the immediate children of the window, in the order they are
+    /// drawn.
+    fn children_mut(&mut self) -> Vec<&mut dyn Widget> {
+        self.children.iter_mut()
+            .chain(self.absolute_children.iter_mut())
+            .map(|(_, child)| &mut **child as &mut dyn Widget)
+            .collect()
+    }
+
+    /// Sends `event` down the chain of children given by `path`, letting it bubble
+    /// back up if it isn't handled. Returns whether any widget handled it.
+    /// 
+    /// Callbacks get access to the window's application state, and the window is
+    /// redrawn if the event was handled or a callback asked for it.
+    fn dispatch_event(&mut self, path: &[usize], event: Event) -> bool {
+        let mut ctx = Context::new(self.state_.as_deref_mut());
+        let child = match path.split_first() {
+            Some((first, rest)) => self.children.iter_mut()
+                .chain(self.absolute_children.iter_mut())
+                .nth(*first)
+                .map(|(_, child)| (child, rest)),
+            None => None,
+        };
+        let handled = match child {
+            Some((child, rest)) => dispatch_event(&mut **child, rest, event, &mut ctx),
+            None => false,
+        };
+
+        // Do whatever the callbacks asked for
+        let focus = ctx.focus_requested();
+        let redraw = ctx.redraw_requested();
+        self.quitting |= ctx.quit_requested();
+        if let Some(depth) = focus {
+            self.set_focus(path[..path.len().saturating_sub(depth)].to_vec());
+        }
+        if handled || redraw {
+            self.redraw();
+        }
+        handled
+    }
+
+    /// Draws all the child widgets based on the terminal's width and height. The
+    /// first frame also sets up the screen with [`Backend::enter()`], e.g.
+    /// entering raw mode and the alternate screen. Fails if the terminal can't be
+    /// written to, or if a child can't be laid out.
+    pub fn draw_children(&mut self) -> Result<()> {
+
+        // A window that quit has left the screen for good
+        if self.quitting {
+            return Ok(());
+        }
+        self.backend.enter()?;
+
+        // Update the grid's size, and start the next frame from a blank buffer
+        self.update_grid_size()?;
+        self.buffer.resize(self.screen_width, self.screen_height);
+
+        // Place each child in the rows and columns it spans
+        let screen = (0, 0, self.screen_width, self.screen_height);
+        draw_grid(&mut self.children, &self.grid, &mut self.buffer, screen)?;
+
+        // Draw the children that were placed in characters at their natural size
+        for (_, child) in &mut self.absolute_children {
+            let (x, y) = (child.get_x(), child.get_y());
+            child.draw(&mut self.buffer, x, y, 0, 0)?;
+        }
+
+        // Write the new frame to the screen
+        self.flush_buffer()
+    }
+
+    /// Writes all the cells that changed since the last frame to the screen, in
+    /// a single flush.
+    fn flush_buffer(&mut self) -> Result<()> {
+
+        // The cursor position and colors the terminal is currently using, so we
+        // only send the commands that actually change something
+        let mut position: Option<(u16, u16)> = None;
+        let mut style: Option<(Color, Color, Attributes)> = None;
+
+        for (x, y, cell) in self.previous.diff(&self.buffer) {
+
+            // Cells covered by a wide grapheme are printed along with it
+            if cell.symbol.is_empty() { continue; }
+            if position != Some((x, y)) {
+                self.backend.move_to(x, y)?;
+            }
+            if style != Some((cell.fg, cell.bg, cell.attributes)) {
+                self.backend.set_attributes(cell.attributes)?;
+                self.backend.set_fg(cell.fg)?;
+                self.backend.set_bg(cell.bg)?;
+                style = Some((cell.fg, cell.bg, cell.attributes));
+            }
+            self.backend.print(&cell.symbol)?;
+            position = Some((x + text::grapheme_width(&cell.symbol) as u16, y));
+        }
+        self.backend.reset_style()?;
+        self.backend.flush()?;
+
+        // The frame we just wrote is now the one on the screen
+        self.previous = self.buffer.clone();
+        Ok(())
+    }
+
+    /// Moves the focus to the next focusable widget in the focus chain, wrapping
+    /// around at the end. This is what pressing Tab does.
+    pub fn focus_next(&mut self) {
+        self.move_focus(true);
+    }
+
+    /// Moves the focus to the previous focusable widget in the focus chain,
+    /// wrapping around at the start. This is what pressing Shift+Tab does.
+    pub fn focus_previous(&mut self) {
+        self.move_focus(false);
+    }
+
+    /// Returns the widget with the ID `id`, if it is in the window and of type `T`
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// let input = window.grid(Box::new(TextInput::new()), 1, 1, 1, 1);
+    /// 
+    /// assert_eq!(window.get::<TextInput>(input).unwrap().get_value(), "");
+    /// assert!(window.get::<Label>(input).is_none());
+    /// # }
+    /// ```
+    pub fn get<T: Widget>(&self, id: WidgetId) -> Option<&T> {
+        let widget: &dyn Any = find_child([&self.children, &self.absolute_children], id)?;
+        widget.downcast_ref::<T>()
+    }
+
+    /// Returns the widget with the ID `id` mutably, if it is in the window and of
+    /// type `T`. Widgets that keep track of their own changes, like a [`Label`]
+    /// whose text was set, are drawn again the next time the window updates;
+    /// other changes show up the next time the whole window is drawn.
+    pub fn get_mut<T: Widget>(&mut self, id: WidgetId) -> Option<&mut T> {
+        let lists = [&mut self.children, &mut self.absolute_children];
+        let widget: &mut dyn Any = find_child_mut(lists, id)?;
+        widget.downcast_mut::<T>()
+    }
+
+    /// Returns the application state set with [`Window::state()`], if there is
+    /// one and it is of type `T`
+    pub fn get_state<T: Any>(&mut self) -> Option<&mut T> {
+        self.state_.as_deref_mut()?.downcast_mut::<T>()
+    }
+
+    /// Draws all the child widgets like [`Window::draw_children()`], but keeps the
+    /// error if there is one, for [`Window::run()`] to return.
+    fn redraw(&mut self) {
+        if let Err(error) = self.draw_children() {
+            self.error.get_or_insert(error);
+        }
+    }
+
+    /// Returns the error kept from an earlier frame, if there is one, or else
+    /// draws the first frame of [`Window::run()`], which also sets up the screen
+    fn start(&mut self) -> Result<()> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.draw_children(),
+        }
+    }
+
+    /// Draws again just the widgets that changed since they were last drawn, e.g. a
+    /// [`Label`] whose text was set, and writes them to the screen, keeping the
+    /// error if there is one. Everything is drawn again if one of them changed
+    /// size.
+    fn redraw_dirty(&mut self) {
+        if self.quitting { return; }
+        let children = self.children.iter_mut()
+            .chain(self.absolute_children.iter_mut())
+            .map(|(_, child)| &mut **child as &mut dyn Widget)
+            .collect();
+        let result = match draw_dirty(children, &mut self.buffer) {
+            Ok((_, true)) => self.draw_children(),
+            Ok((true, false)) => self.flush_buffer(),
+            Ok((false, false)) => Ok(()),
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            self.error.get_or_insert(error);
+        }
+    }
+
+    /// Hands every message posted through a [`Sender`] since the last call to the
+    /// handler for its type, and runs every posted closure. Returns whether
+    /// anything was posted.
+    fn handle_messages(&mut self) -> bool {
+        let mut handled = false;
+        while let Ok(message) = self.messages.try_recv() {
+            handled = true;
+            match message {
+                Message::Call(callback) => callback(self),
+                Message::User(mut message) => {
+
+                    // Take the handlers out while they run, since they get the
+                    // window itself; a message nobody handles is dropped
+                    let mut handlers = std::mem::take(&mut self.message_handlers);
+                    for handler in &mut handlers {
+                        match handler(self, message) {
+                            Ok(()) => break,
+                            Err(unhandled) => message = unhandled,
+                        }
+                    }
+                    handlers.append(&mut self.message_handlers);
+                    self.message_handlers = handlers;
+                },
+            }
+        }
+        handled
+    }
+
+    /// Create a window that doesn't touch the terminal at all: no raw mode, no
+    /// alternate screen, and a fixed size of `width`×`height`. Everything is drawn
+    /// into a [`MemoryBackend`], and can be inspected with [`Window::screen()`].
+    /// 
+    /// Use this for testing, together with [`Window::send_event()`]:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// let label = Label::builder()
+    ///     .text(String::from("Hello"))
+    ///     .build();
+    /// window.grid(Box::new(label), 1, 1, 1, 1);
+    /// 
+    /// assert!(window.screen().find("Hello").is_some());
+    /// # }
+    /// ```
+    pub fn headless(width: u16, height: u16) -> Window {
+        Window::builder()
+            .backend(MemoryBackend::new(width, height))
+            .build()
+    }
+
+    /// Runs `change`, which may move the window's widgets around, and then finds
+    /// the widgets that had the focus and the hover highlight again, so that they
+    /// keep them. If they are no longer in the window, nothing has them.
+    fn keep_paths<R>(&mut self, change: impl FnOnce(&mut Window) -> R) -> R {
+        let (focus, hover) = (self.focus.clone(), self.hover.clone());
+        let focused = widget_at_path(self.children_mut(), &focus).map(|widget| address(widget));
+        let hovered = widget_at_path(self.children_mut(), &hover).map(|widget| address(widget));
+
+        let result = change(self);
+        self.focus = focused
+            .and_then(|focused| path_to(self.children_mut(), focused))
+            .unwrap_or_default();
+        self.hover = hovered
+            .and_then(|hovered| path_to(self.children_mut(), hovered))
+            .unwrap_or_default();
+        result
+    }
+
+    /// Moves the focus one step along the focus chain; forwards if `forward`,
+    /// otherwise backwards.
+    fn move_focus(&mut self, forward: bool) {
+        let mut chain = Vec::new();
+        focus_chain(self.children_mut(), &mut Vec::new(), &mut chain);
+        if chain.is_empty() { return; }
+
+        // Find the widget after (or before) the one with the focus; if nothing
+        // has the focus, start at the beginning (or end) of the chain
+        let len = chain.len();
+        let next = match chain.iter().position(|path| *path == self.focus) {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None if forward => 0,
+            None => len - 1,
+        };
+        if self.set_focus(chain.swap_remove(next)) {
+            self.redraw();
+        }
+    }
+
+    /// Returns when the window next has to be updated with [`Window::update()`]:
+    /// when the next timer or tick is due, or when it should check for messages
+    /// posted through its [`Sender`]s. Returns [`None`] if the window only has to
+    /// react to events.
+    /// 
+    /// This is useful for writing an event loop by hand, e.g. one that waits for
+    /// other things too; see `Window::run_stream()`, with the `tokio` feature.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let due = match (self.timers.next_due(), self.next_tick) {
+            (Some(timer), Some(tick)) => Some(timer.min(tick)),
+            (timer, tick) => timer.or(tick),
+        };
+
+        // Messages can't interrupt waiting for events, so check for them regularly
+        if self.listening {
+            let check = Instant::now() + Duration::from_millis(20);
+            return Some(due.map_or(check, |due| due.min(check)));
+        }
+        due
+    }
+
+    /// Quits the window and the alternate screen. This also happens when the
+    /// window is dropped, so the terminal is restored even after a panic.
+    pub fn quit(&mut self) {
+        self.quitting = true;
+        if let Err(error) = self.backend.leave() {
+            self.error.get_or_insert(Error::Io(error));
+        }
+    }
+
+    /// Moves the widget with the ID `id` into the parent widget with the ID
+    /// `parent`, or into the window itself if `parent` is [`None`], at row `row`
+    /// and column `col`, spanning `rowspan` rows and `colspan` columns. The widget
+    /// keeps its ID, and the focus if it has it.
+    /// 
+    /// Returns [`false`], leaving everything as it was, if there is no widget
+    /// `id`, if `parent` isn't a parent widget in the window that can hold
+    /// children, e.g. because it is a [`Tabs`] without pages, or if it is inside
+    /// the widget being moved.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// let frame = window.grid(Box::new(Frame::new()), 1, 1, 3, 3);
+    /// let label = window.grid(Box::new(Label::new()), 5, 5, 1, 1);
+    /// 
+    /// assert!(window.reparent(label, Some(frame), 1, 1, 1, 1));
+    /// assert!(window.get::<Label>(label).is_some());
+    /// # }
+    /// ```
+    pub fn reparent(
+        &mut self,
+        id: WidgetId,
+        parent: Option<WidgetId>,
+        row: u16,
+        col: u16,
+        rowspan: u16,
+        colspan: u16
+    ) -> bool {
+
+        // Make sure the widget can go where it is asked to before taking it out
+        let widget = match find_child([&self.children, &self.absolute_children], id) {
+            Some(widget) => widget,
+            None => return false,
+        };
+        if let Some(parent) = parent {
+            if parent == id || widget.find(parent).is_some() {
+                return false;
+            }
+            let lists = [&mut self.children, &mut self.absolute_children];
+            let target = find_child_mut(lists, parent).and_then(|parent| parent.as_parent());
+            if !target.is_some_and(|target| target.can_hold()) {
+                return false;
+            }
+        }
+
+        self.keep_paths(|window| {
+            let lists = [&mut window.children, &mut window.absolute_children];
+            let child = match remove_child(lists, id) {
+                Some(child) => child,
+                None => return,
+            };
+            match parent {
+                Some(parent) => {
+                    let lists = [&mut window.children, &mut window.absolute_children];
+                    let parent = find_child_mut(lists, parent)
+                        .and_then(|parent| parent.as_parent());
+                    if let Some(parent) = parent {
+                        parent.grid_with_id(id, child, row, col, rowspan, colspan);
+                    }
+                },
+                None => grid_child(&mut window.children, id, child, row, col, rowspan, colspan),
+            }
+        });
+        self.redraw();
+        true
+    }
+
+    /// Run the application; this starts the event listener. Between events, the
+    /// window wakes up whenever a timer or a tick is due, and picks up the messages
+    /// posted through its [`Sender`]s.
+    /// 
+    /// Returns once the window quits, or with the first error that came up, e.g.
+    /// when the terminal can't be read from or a widget can't be laid out. The
+    /// terminal is restored either way once the window is dropped.
+    pub fn run(&mut self) -> Result<()> {
+
+        // Set up the screen with a first frame, even without any children, so
+        // the terminal is in raw mode before the first event is read
+        self.start()?;
+
+        // Start the event listener
+        loop {
+
+            // Wait for the next event, but only until the window has to update
+            let ready = match self.next_deadline() {
+                Some(due) => poll(due.saturating_duration_since(Instant::now()))?,
+                None => true,
+            };
+            let running = !ready || self.send_event(read()?);
+            let running = running && self.update(Instant::now());
+            if let Some(error) = self.error.take() {
+                return Err(error);
+            }
+            if !running {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Handle `event` exactly like the event listener started by [`Window::run()`]
+    /// would. Returns [`false`] if the event made the window quit.
+    /// 
+    /// This is mostly useful for testing, together with [`Window::headless()`].
+    pub fn send_event(&mut self, event: Event) -> bool {
+        match event {
+            Event::Key(
+                KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::CONTROL }
+            ) => {
+                self.quit();
+                return false;
+            },
+            Event::Key(key) => {
+                let path = self.focus.clone();
+                if !self.dispatch_event(&path, event) {
+
+                    // Nothing handled the key, so use it for moving the focus
+                    match key.code {
+                        KeyCode::Tab => self.focus_next(),
+                        KeyCode::BackTab => self.focus_previous(),
+                        _ => {},
+                    }
+                }
+            },
+            Event::Mouse(mouse) => {
+                let path = path_at(self.children_mut(), mouse.column, mouse.row);
+
+                // Let the widgets know if the pointer moved onto or off of them
+                let mut redraw = false;
+                if path != self.hover {
+                    let old = std::mem::replace(&mut self.hover, path.clone());
+                    if let Some(widget) = widget_at_path(self.children_mut(), &old) {
+                        widget.hover_remove();
+                    }
+                    if let Some(widget) = widget_at_path(self.children_mut(), &path) {
+                        widget.hover_add();
+                    }
+                    redraw = true;
+                }
+
+                // Clicking on a widget gives the focus to the innermost focusable
+                // widget under the pointer
+                if let MouseEventKind::Down(_) = mouse.kind {
+                    for length in (1..=path.len()).rev() {
+                        let focusable = widget_at_path(self.children_mut(), &path[..length])
+                            .is_some_and(|widget| widget.as_focusable().is_some());
+                        if focusable {
+                            redraw |= self.set_focus(path[..length].to_vec());
+                            break;
+                        }
+                    }
+                }
+                if !self.dispatch_event(&path, event) && redraw {
+                    self.redraw();
+                }
+            }
+            Event::Resize(width, height) => {
+                self.screen_height = height;
+                self.screen_width = width;
+                self.redraw();
+            }
+        }
+
+        // A callback may have asked the window to quit
+        if self.quitting {
+            self.quit();
+            return false;
+        }
+        true
+    }
+
+    /// Run the application asynchronously on a [`tokio`] runtime, reading the
+    /// terminal's events from crossterm's [`EventStream`]. Otherwise this works
+    /// just like [`Window::run()`], but doesn't block the thread it runs on.
+    /// Only available with the `tokio` feature.
+    /// 
+    /// For example:
+    /// 
+    /// ```no_run
+    /// use rusty_panther::prelude::*;
+    /// 
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let mut window = widgets::Window::new();
+    ///     window.run_async().await.unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn run_async(&mut self) -> Result<()> {
+        self.run_stream(EventStream::new()).await
+    }
+
+    /// Run the application asynchronously on a [`tokio`] runtime, like
+    /// [`Window::run_async()`], but reading the events from `events` instead of
+    /// the terminal. Returns once the window quits, or `events` ends. Only
+    /// available with the `tokio` feature.
+    /// 
+    /// To wait for other things at the same time, e.g. channels of your own, write
+    /// the loop by hand instead, using [`Window::next_deadline()`]:
+    /// 
+    /// ```no_run
+    /// use rusty_panther::prelude::*;
+    /// 
+    /// use crossterm::event::EventStream;
+    /// use futures_util::StreamExt;
+    /// use std::time::Instant;
+    /// 
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let mut window = widgets::Window::new();
+    ///     let mut events = EventStream::new();
+    ///     let (_lines, mut received) = tokio::sync::mpsc::unbounded_channel::<String>();
+    ///     loop {
+    ///         let deadline = window.next_deadline();
+    ///         let wait = async {
+    ///             match deadline {
+    ///                 Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+    ///                 None => std::future::pending().await,
+    ///             }
+    ///         };
+    ///         tokio::select! {
+    ///             Some(Ok(event)) = events.next() => {
+    ///                 if !window.send_event(event) { break; }
+    ///             },
+    ///             Some(_line) = received.recv() => {
+    ///                 // Do something with the line
+    ///             },
+    ///             _ = wait => {},
+    ///         }
+    ///         if !window.update(Instant::now()) { break; }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn run_stream(
+        &mut self,
+        mut events: impl Stream<Item = std::io::Result<Event>> + Unpin
+    ) -> Result<()> {
+        self.start()?;
+        loop {
+
+            // Wait for the next event, but only until the window has to update;
+            // without a deadline, only an event can wake the window up
+            let deadline = self.next_deadline();
+            let wait = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let running = tokio::select! {
+                event = events.next() => match event {
+                    Some(event) => self.send_event(event?),
+                    None => false,
+                },
+                _ = wait => true,
+            };
+            let running = running && self.update(Instant::now());
+            if let Some(error) = self.error.take() {
+                return Err(error);
+            }
+            if !running {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Returns a new [`Sender`], which other threads can use to post messages and
+    /// closures to this window while it runs.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// let sender = window.sender();
+    /// std::thread::spawn(move || sender.call(|window| window.quit()));
+    /// # }
+    /// ```
+    pub fn sender(&mut self) -> Sender {
+        self.listening = true;
+        Sender::new(self.sender_.clone())
+    }
+
+    /// Returns the first error that came up where it couldn't be returned, e.g.
+    /// while redrawing after a child was gridded or an event was sent, and forgets
+    /// it. [`Window::run()`] returns these errors itself, but windows that are
+    /// driven by hand, e.g. with [`Window::headless()`], should check for them.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # use rusty_panther::Error;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::headless(80, 24);
+    /// window.grid(Box::new(Label::new()), 9, 1, 1, 1);
+    /// assert!(matches!(window.take_error(), Some(Error::NoSuchRow(9))));
+    /// assert!(window.take_error().is_none());
+    /// # }
+    /// ```
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// Gives the focus to the widget that `path` leads to, taking it away from the
+    /// widget that had it. Returns whether the focus changed.
+    fn set_focus(&mut self, path: Vec<usize>) -> bool {
+        if path == self.focus { return false; }
+        let old = std::mem::replace(&mut self.focus, path);
+        if let Some(focusable) = widget_at_path(self.children_mut(), &old)
+            .and_then(|widget| widget.as_focusable()) {
+            focusable.focus_remove();
+        }
+        let new = self.focus.clone();
+        if let Some(focusable) = widget_at_path(self.children_mut(), &new)
+            .and_then(|widget| widget.as_focusable()) {
+            focusable.focus_add();
+        }
+        true
+    }
+
+    /// Handles the messages posted through the window's [`Sender`]s, runs every
+    /// timer that is due at `now`, and gives every widget a tick if one is due,
+    /// exactly like the event listener started by [`Window::run()`] would.
+    /// Redraws if anything changed, or otherwise draws just the widgets that were
+    /// changed directly, e.g. through [`Window::get_mut()`]. Returns [`false`] if
+    /// a message or a timer made the window quit.
+    /// 
+    /// This is mostly useful for testing, together with [`Window::headless()`].
+    pub fn update(&mut self, now: Instant) -> bool {
+
+        // A message handler can quit the window itself
+        let mut changed = self.handle_messages();
+        if self.quitting {
+            return false;
+        }
+
+        let mut ctx = Context::new(self.state_.as_deref_mut());
+        changed |= self.timers.run_due(now, &mut ctx);
+
+        // Give every widget a tick, and schedule the next one, skipping any ticks
+        // that were missed
+        if let (Some(rate), Some(next_tick)) = (self.tick_rate_, self.next_tick) {
+            if next_tick <= now {
+                let children = self.children.iter_mut()
+                    .chain(self.absolute_children.iter_mut())
+                    .map(|(_, child)| &mut **child as &mut dyn Widget)
+                    .collect();
+                changed |= tick_all(children, &mut ctx);
+                let mut next = next_tick;
+                while next <= now {
+                    next += rate;
+                }
+                self.next_tick = Some(next);
+            }
+        }
+
+        // Do whatever the callbacks asked for
+        changed |= ctx.redraw_requested();
+        self.quitting |= ctx.quit_requested();
+        if changed {
+            self.redraw();
+        }
+        else {
+            self.redraw_dirty();
+        }
+        if self.quitting {
+            self.quit();
+            return false;
+        }
+        true
+    }
+
+    /// Updates the grid size based on the terminal size.
+    fn update_grid_size(&mut self) -> Result<()> {
+        let (width, height) = self.backend.size().map_err(Error::TerminalSize)?;
+        self.screen_height = height;
+        self.screen_width = width;
+        self.grid.set_height_chars(height);
+        self.grid.set_width_chars(width);
+        Ok(())
+    }
+    
+    // The builder functions. These can be used to optionally customize options.
+    // Be sure to call [`build()`] to finalize the creation.
+
+    /// Set the [`Backend`] that the window is drawn to. By default this is a
+    /// [`CrosstermBackend`] writing to stdout. Use when building the window.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::backend::MemoryBackend;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::builder()
+    ///     .backend(MemoryBackend::new(80, 24))
+    ///     .build();
+    /// # window.quit();
+    /// # }
+    /// ```
+    pub fn backend(mut self, backend: impl Backend + 'static) -> Window {
+        self.backend = BackendGuard::new(Box::new(backend));
+        self
+    }
+
+    /// Add a `handler` for the messages of type `M` posted through the window's
+    /// [`Sender`]s. The handler gets the window itself, so it can change its
+    /// widgets and its state; the window redraws once all the messages are
+    /// handled. Use when building the window.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::backend::MemoryBackend;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// enum Download { Progress(f64), Done }
+    /// 
+    /// let mut window = Window::builder()
+    ///     .backend(MemoryBackend::new(80, 24))
+    ///     .on_message(|window: &mut Window, download: Download| {
+    ///         if let Download::Done = download { window.quit(); }
+    ///     })
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn on_message<M: Any + Send>(
+        mut self,
+        mut handler: impl FnMut(&mut Window, M) + 'static
+    ) -> Window {
+        self.message_handlers.push(Box::new(move |window, message| {
+            handler(window, *message.downcast::<M>()?);
+            Ok(())
+        }));
+        self
+    }
+
+    /// Set whether to install a panic hook that restores the terminal before the
+    /// panic message is printed; see [`backend::set_panic_hook()`]. The hook is
+    /// installed when the first such window is built, and stays installed. Off by
+    /// default. Use when building the window.
+    /// 
+    /// For example:
+    /// 
+    /// ```no_run
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::builder()
+    ///     .panic_hook(true)
+    ///     .build();
+    /// # window.quit();
+    /// # }
+    /// ```
+    pub fn panic_hook(mut self, panic_hook: bool) -> Window {
+        self.panic_hook_ = panic_hook;
+        self
+    }
+
+    /// Set the application state that callbacks can access with
+    /// [`Context::state()`]. Use when building the window.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::backend::MemoryBackend;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// struct Counter { clicks: u32 }
+    /// 
+    /// let mut window = Window::builder()
+    ///     .backend(MemoryBackend::new(80, 24))
+    ///     .state(Counter { clicks: 0 })
+    ///     .build();
+    /// # assert_eq!(window.get_state::<Counter>().unwrap().clicks, 0);
+    /// # }
+    /// ```
+    pub fn state<T: Any>(mut self, state: T) -> Window {
+        self.state_ = Some(Box::new(state));
+        self
+    }
+
+    /// Set the theme for the window. Use when building the window.
+    /// 
+    /// For example:
+    /// 
+    /// ```no_run
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::widgets::*;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::builder()
+    ///     .theme(default_theme())
+    ///     .build();
+    /// # window.quit();
+    /// # }
+    /// ```
+    pub fn theme(mut self, theme: Theme) -> Window {
+        self.theme_ = theme;
+        self
+    }
+
+    /// Give every widget a tick every `rate`, e.g. to animate spinners and
+    /// indeterminate progress bars. A rate of zero turns ticks off, which is the
+    /// default. Use when building the window.
+    /// 
+    /// For example:
+    /// 
+    /// ```
+    /// # use rusty_panther::prelude::*;
+    /// # use rusty_panther::backend::MemoryBackend;
+    /// # use rusty_panther::widgets::*;
+    /// # use std::time::Duration;
+    /// # 
+    /// # fn main() {
+    /// let mut window = Window::builder()
+    ///     .backend(MemoryBackend::new(80, 24))
+    ///     .tick_rate(Duration::from_millis(100))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn tick_rate(mut self, rate: Duration) -> Window {
+        self.tick_rate_ = if rate.is_zero() { None } else { Some(rate) };
+        self
+    }
+}
+impl Buildable for Window {
+
+    fn build(self) -> Window {
+
+        // The screen is set up with the first frame, so building never fails
+        if self.panic_hook_ {
+            backend::set_panic_hook();
+        }
+        Window {
+            absolute_children: self.absolute_children,
+            backend: self.backend,
+            buffer: self.buffer,
+            children: self.children,
+            error: self.error,
+            focus: self.focus,
+            grid: self.grid,
+            hover: self.hover,
+            listening: self.listening,
+            message_handlers: self.message_handlers,
+            messages: self.messages,
+            next_tick: self.tick_rate_.map(|rate| Instant::now() + rate),
+            panic_hook_: self.panic_hook_,
+            previous: self.previous,
+            quitting: self.quitting,
+            screen_height: self.screen_height,
+            screen_width: self.screen_width,
+            sender_: self.sender_,
+            state_: self.state_,
+            theme_: self.theme_,
+            tick_rate_: self.tick_rate_,
+            timers: self.timers,
+        }
+    }
+
+    fn builder() -> Window {
+        let (sender, messages) = mpsc::channel();
+        Window {
+            absolute_children: Vec::new(),
+            backend: BackendGuard::new(Box::new(CrosstermBackend::stdout())),
+            buffer: Buffer::new(0, 0),
+            children: Vec::new(),
+            error: None,
+            focus: Vec::new(),
+            grid: Grid::new(),
+            hover: Vec::new(),
+            listening: false,
+            message_handlers: Vec::new(),
+            messages,
+            next_tick: None,
+            panic_hook_: false,
+            previous: Buffer::new(0, 0),
+            quitting: false,
+            screen_height: 0,
+            screen_width: 0,
+            sender_: sender,
+            state_: None,
+            theme_: default_theme(),
+            tick_rate_: None,
+            timers: Timers::default(),
+        }
+    }
+
+    fn new() -> Window {
+        Window::builder().build()
+    }
+}
+impl Parent for Window {
+    fn add_with_id(&mut self, id: WidgetId, child: Box<dyn Widget>, x: u16, y: u16) {
+        self.keep_paths(|window| add_child(&mut window.absolute_children, id, child, x, y));
+
+        // Redraw the children
+        self.redraw();
+    }
+
+    fn grid_with_id(&mut self,
+        id: WidgetId,
+        child: Box<dyn Widget>,
+        row: u16,
+        col: u16,
+        rowspan: u16,
+        colspan: u16) {
+        self.keep_paths(|window| {
+            grid_child(&mut window.children, id, child, row, col, rowspan, colspan)
+        });
+        
+        // Redraw the children
+        self.redraw();
+    }
+
+    fn remove(&mut self, id: WidgetId) -> Option<Box<dyn Widget>> {
+        let mut removed = self.keep_paths(|window| {
+            remove_child([&mut window.children, &mut window.absolute_children], id)
+        })?;
+
+        // The widget may have had the focus or the hover highlight
+        hide(vec![&mut *removed]);
+        self.redraw();
+        Some(removed)
     }
 }
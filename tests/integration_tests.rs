@@ -1,42 +1,1266 @@
 use rusty_panther::prelude::*;
-use rusty_panther::widgets::{Label, Window};
+use rusty_panther::widgets::{
+    Button,
+    Frame,
+    Gauge,
+    Label,
+    ListView,
+    ProgressBar,
+    Table,
+    Tabs,
+    TextArea,
+    TextInput,
+    Tree,
+    TreeNode,
+    Window,
+};
+
+use rusty_panther::{Error, Result};
+use rusty_panther::backend::MemoryBackend;
+use rusty_panther::buffer::Buffer;
+
+#[cfg(feature = "tokio")]
+use std::cell::RefCell;
+#[cfg(feature = "tokio")]
+use std::io::Write;
+#[cfg(feature = "tokio")]
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crossterm::style::Attribute;
+
+use crossterm::event::{
+    Event,
+    KeyCode,
+    KeyEvent,
+    KeyModifiers,
+    MouseButton,
+    MouseEvent,
+    MouseEventKind,
+};
+
+/// A widget that counts the mouse clicks it gets, used for testing event dispatch
+#[derive(Default)]
+struct ClickCounter {
+    area: (u16, u16, u16, u16),
+    clicks: u32,
+    x: u16,
+    y: u16,
+}
+impl Eventable for ClickCounter {
+    fn event_send(&mut self, event: Event, _ctx: &mut Context) -> bool {
+        match event {
+            Event::Mouse(MouseEvent { kind: MouseEventKind::Down(_), .. }) => {
+                self.clicks += 1;
+                true
+            },
+            _ => false,
+        }
+    }
+}
+impl Widget for ClickCounter {
+    fn as_eventable(&mut self) -> Option<&mut dyn Eventable> { Some(self) }
+    fn draw(
+        &mut self,
+        _buffer: &mut Buffer,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16
+    ) -> Result<()> {
+        self.area = (x, y, width, height);
+        Ok(())
+    }
+    fn get_area(&self) -> (u16, u16, u16, u16) { self.area }
+    fn get_span(&self) -> (u16, u16) { (1, 1) }
+    fn get_x(&self) -> u16 { self.x }
+    fn get_y(&self) -> u16 { self.y }
+    fn set_span(&mut self, _rowspan: u16, _colspan: u16) {}
+    fn set_x(&mut self, x: u16) { self.x = x; }
+    fn set_y(&mut self, y: u16) { self.y = y; }
+}
+
+/// Returns a left mouse click at (`column`, `row`)
+fn click(column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
 
 #[test]
-/// Creates a new [`Window`] that has a [`Label`].
+/// Creates a new headless [`Window`] that has some [`Label`]s, and checks that
+/// they are drawn where the grid puts them.
 fn user_interaction() {
-    
+
     // Create the window and its label
-    let mut window = Window::new();
+    let mut window = Window::headless(80, 24);
 
-    let mut label1 = Label::builder()
+    let label1 = Label::builder()
         // .border((false, false))
         .text(String::from("Label 1"))
         .build();
-    let mut label2 = Label::builder()
+    let label2 = Label::builder()
         .text(String::from("Label 2"))
         .build();
-    let mut label3 = Label::builder()
+    let label3 = Label::builder()
         .text(String::from("Label 3"))
         .build();
-    let mut label4 = Label::builder()
+    let label4 = Label::builder()
         .text(String::from("Label 4"))
         .build();
-    let mut label5 = Label::builder()
+    let label5 = Label::builder()
         .text(String::from("Label 5"))
         .build();
-    let mut label6 = Label::builder()
+    let label6 = Label::builder()
         .text(String::from("Label 6"))
         .build();
-    window.grid(Box::new(&mut label1), 1, 1, 1, 1);
-    window.grid(Box::new(&mut label2), 2, 1, 1, 1);
-    window.grid(Box::new(&mut label3), 1, 2, 1, 1);
-    window.grid(Box::new(&mut label4), 1, 3, 1, 1);
-    window.grid(Box::new(&mut label5), 3, 1, 1, 1);
-    window.grid(Box::new(&mut label6), 3, 2, 1, 1);
+    window.grid(Box::new(label1), 1, 1, 1, 1);
+    window.grid(Box::new(label2), 2, 1, 1, 1);
+    window.grid(Box::new(label3), 1, 2, 1, 1);
+    window.grid(Box::new(label4), 1, 3, 1, 1);
+    window.grid(Box::new(label5), 3, 1, 1, 1);
+    window.grid(Box::new(label6), 3, 2, 1, 1);
+
+    // Each label's text sits just inside its border, at the top left of its cell
+    let buffer = window.screen();
+    assert_eq!(buffer.get_width(), 80);
+    assert_eq!(buffer.get_height(), 24);
+    assert_eq!(buffer.find("Label 1"), Some((2, 2)));
+    assert_eq!(buffer.find("Label 3"), Some((17, 2)));
+    assert_eq!(buffer.find("Label 2"), Some((2, 6)));
+    assert_eq!(buffer.cell(16, 1).unwrap().symbol, "╭");
+    assert_eq!(buffer.cell(17, 2).unwrap().fg, default_theme().get_fg_rgb());
+
+    // Ctrl+C quits the window
+    let ctrl_c = KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::CONTROL };
+    assert!(window.send_event(Event::Resize(80, 24)));
+    assert!(!window.send_event(Event::Key(ctrl_c)));
+}
+
+#[test]
+/// Checks that a [`Label`] gridded with a rowspan and colspan covers the combined
+/// area of all those rows and columns.
+fn grid_span() {
+    let mut window = Window::headless(80, 24);
+
+    let label = Label::builder()
+        .text(String::from("Spanning"))
+        .build();
+    window.grid(Box::new(label), 1, 1, 2, 3);
+
+    // The border runs along the top of three columns and down two rows
+    let buffer = window.screen();
+    assert_eq!(buffer.cell(1, 1).unwrap().symbol, "╭");
+    assert_eq!(buffer.cell(47, 1).unwrap().symbol, "╮");
+    assert_eq!(buffer.cell(1, 8).unwrap().symbol, "╰");
+    assert_eq!(buffer.cell(47, 8).unwrap().symbol, "╯");
+}
+
+#[test]
+/// Checks that a [`Frame`] lays its children out inside its own area, and that
+/// frames can be nested.
+fn nested_frames() {
+    let mut window = Window::headless(80, 24);
+
+    let label = Label::builder()
+        .text(String::from("Inner label"))
+        .build();
+    let mut inner = Frame::builder()
+        .title(String::from("Inner"))
+        .set_grid(Grid::builder().width(1).height(1).build())
+        .build();
+    inner.grid(Box::new(label), 1, 1, 1, 1);
+    let mut outer = Frame::builder()
+        .title(String::from("Outer"))
+        .set_grid(Grid::builder().width(2).height(2).build())
+        .build();
+    outer.grid(Box::new(inner), 2, 2, 1, 1);
+    window.grid(Box::new(outer), 1, 1, 5, 5);
+
+    // The outer frame covers the whole grid, the inner frame sits in its bottom
+    // right quarter, and the label sits inside the inner frame
+    let buffer = window.screen();
+    let (outer_x, outer_y) = buffer.find("Outer").unwrap();
+    let (inner_x, inner_y) = buffer.find("Inner").unwrap();
+    let (label_x, label_y) = buffer.find("Inner label").unwrap();
+    assert_eq!((outer_x, outer_y), (4, 1));
+    assert!(inner_x > 40 && inner_y > 10);
+    assert!(label_x > inner_x - 2 && label_y > inner_y);
+    assert_eq!(buffer.cell(1, 22).unwrap().symbol, "╰");
+
+    // The children stay inside the outer frame's right-hand border
+    assert_eq!(buffer.cell(78, label_y).unwrap().symbol, "│");
+}
+
+#[test]
+/// Checks that mouse events are sent to the widget under the pointer, even when
+/// it is nested inside of a [`Frame`].
+fn mouse_dispatch() {
+    let mut window = Window::headless(80, 24);
+    let label = Label::builder()
+        .text(String::from("Not clickable"))
+        .build();
+    let mut frame = Frame::builder()
+        .set_grid(Grid::builder().width(1).height(1).build())
+        .build();
+    let counter = frame.grid(Box::new(ClickCounter::default()), 1, 1, 1, 1);
+    window.grid(Box::new(frame), 1, 1, 2, 2);
+    window.grid(Box::new(label), 4, 4, 1, 1);
+
+    // Click on the counter (inside the frame's border) twice, then on the label
+    // and on empty space
+    assert!(window.send_event(click(3, 3)));
+    assert!(window.send_event(click(20, 6)));
+    let (label_x, label_y) = window.screen().find("Not clickable").unwrap();
+    assert!(window.send_event(click(label_x, label_y)));
+    assert!(window.send_event(click(79, 23)));
+    assert_eq!(window.get::<ClickCounter>(counter).unwrap().clicks, 2);
+}
+
+/// Returns a key press of `code` with `modifiers`
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent { code, modifiers })
+}
+
+#[test]
+/// Checks that Tab and Shift+Tab move the focus along the focusable widgets,
+/// that clicking gives a widget the focus, and that the focused widget's border
+/// is highlighted.
+fn focus_traversal() {
+    let mut window = Window::headless(80, 24);
+
+    let first = Label::builder()
+        .text(String::from("First"))
+        .focusable(true)
+        .build();
+    let skipped = Label::builder()
+        .text(String::from("Skipped"))
+        .build();
+    let second = Label::builder()
+        .text(String::from("Second"))
+        .focusable(true)
+        .build();
+    let mut frame = Frame::builder()
+        .set_grid(Grid::builder().width(1).height(1).build())
+        .build();
+    frame.grid(Box::new(second), 1, 1, 1, 1);
+    window.grid(Box::new(first), 1, 1, 1, 1);
+    window.grid(Box::new(skipped), 1, 2, 1, 1);
+    window.grid(Box::new(frame), 3, 1, 2, 2);
+
+    // Returns the color of the top left corner of the label with `text`
+    let border = |window: &Window, text: &str| {
+        let (x, y) = window.screen().find(text).unwrap();
+        window.screen().cell(x - 1, y - 1).unwrap().fg
+    };
+    let focus = default_theme().get_focus_rgb();
+    let normal = default_theme().get_fg_rgb();
+    assert_eq!(border(&window, "First"), normal);
+
+    // Tab goes through the focusable labels, wrapping around
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    assert_eq!(border(&window, "First"), focus);
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    assert_eq!(border(&window, "First"), normal);
+    assert_eq!(border(&window, "Skipped"), normal);
+    assert_eq!(border(&window, "Second"), focus);
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    assert_eq!(border(&window, "First"), focus);
+
+    // Shift+Tab goes backwards
+    window.send_event(key(KeyCode::BackTab, KeyModifiers::SHIFT));
+    assert_eq!(border(&window, "Second"), focus);
+    assert_eq!(border(&window, "First"), normal);
+
+    // Clicking on the first label focuses it again
+    let (x, y) = window.screen().find("First").unwrap();
+    window.send_event(click(x, y));
+    assert_eq!(border(&window, "First"), focus);
+    assert_eq!(border(&window, "Second"), normal);
+}
+
+/// The application state used for testing callbacks
+struct Clicks {
+    count: u32,
+}
+
+#[test]
+/// Checks that callbacks can change the application state, that unhandled events
+/// bubble up to the parent's callbacks, and that callbacks can quit the window.
+fn callbacks() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Clicks { count: 0 })
+        .build();
+
+    let button = Label::builder()
+        .text(String::from("Click me"))
+        .focusable(true)
+        .on_click(|ctx| ctx.state::<Clicks>().unwrap().count += 1)
+        .on_key(KeyCode::Enter, |ctx| ctx.state::<Clicks>().unwrap().count += 10)
+        .build();
+    let mut frame = Frame::builder()
+        .set_grid(Grid::builder().width(1).height(1).build())
+        .on_key(KeyCode::Esc, |ctx| ctx.quit())
+        .build();
+    frame.grid(Box::new(button), 1, 1, 1, 1);
+    window.grid(Box::new(frame), 1, 1, 2, 2);
+
+    // Clicking focuses the label and runs its click callback, and Enter runs its
+    // key callback
+    let (x, y) = window.screen().find("Click me").unwrap();
+    assert!(window.send_event(click(x, y)));
+    assert!(window.send_event(key(KeyCode::Enter, KeyModifiers::NONE)));
+    assert_eq!(window.get_state::<Clicks>().unwrap().count, 11);
+
+    // The label doesn't handle Esc, so it bubbles up to the frame, which quits
+    assert!(!window.send_event(key(KeyCode::Esc, KeyModifiers::NONE)));
+}
+
+/// Returns a mouse event of `kind` at (`column`, `row`)
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent { kind, column, row, modifiers: KeyModifiers::NONE })
+}
+
+#[test]
+/// Checks that a [`Button`] is activated by clicks, Enter and Space, and that it
+/// is drawn differently when hovered, pressed and focused.
+fn button() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Clicks { count: 0 })
+        .build();
+
+    let button = Button::builder()
+        .text(String::from("Press"))
+        .on_click(|ctx| ctx.state::<Clicks>().unwrap().count += 1)
+        .build();
+    window.grid(Box::new(button), 1, 1, 1, 1);
+    let theme = default_theme();
+    let (x, y) = window.screen().find("Press").unwrap();
+    let corner = |window: &Window| window.screen().cell(1, 1).unwrap().clone();
+    assert_eq!(corner(&window).fg, theme.get_fg_rgb());
+
+    // Hovering highlights the border
+    window.send_event(mouse(MouseEventKind::Moved, x, y));
+    assert_eq!(corner(&window).fg, theme.get_hover_rgb());
+
+    // Pressing changes the background until the button is released, and
+    // activates the button
+    window.send_event(mouse(MouseEventKind::Down(MouseButton::Left), x, y));
+    assert_eq!(corner(&window).bg, theme.get_pressed_rgb());
+    window.send_event(mouse(MouseEventKind::Up(MouseButton::Left), x, y));
+    assert_eq!(corner(&window).bg, theme.get_bg_rgb());
+    assert_eq!(window.get_state::<Clicks>().unwrap().count, 1);
+
+    // Moving away removes the hover highlight, leaving the focus highlight
+    window.send_event(mouse(MouseEventKind::Moved, 79, 23));
+    assert_eq!(corner(&window).fg, theme.get_focus_rgb());
+
+    // Enter and Space activate the focused button
+    window.send_event(key(KeyCode::Enter, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Char(' '), KeyModifiers::NONE));
+    assert_eq!(window.get_state::<Clicks>().unwrap().count, 3);
+}
+
+/// The application state used for testing change callbacks
+struct Changes {
+    last: String,
+}
+
+/// Types every char of `text` into `window`
+fn type_text(window: &mut Window, text: &str) {
+    for c in text.chars() {
+        window.send_event(key(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+}
+
+#[test]
+/// Checks that a [`TextInput`] can be typed into and edited, that it scrolls
+/// with the cursor, and that it reports changes.
+fn text_input() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Changes { last: String::new() })
+        .build();
+
+    let input = TextInput::builder()
+        .placeholder(String::from("Name"))
+        .max_length(20)
+        .on_change(|ctx, value| ctx.state::<Changes>().unwrap().last = value.to_string())
+        .build();
+    let password = TextInput::builder()
+        .password(true)
+        .build();
+    window.grid(Box::new(input), 1, 1, 1, 1);
+    window.grid(Box::new(password), 2, 1, 1, 1);
+    assert!(window.screen().find("Name").is_some());
+
+    // Typing replaces the placeholder, and the editing keys move the cursor
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    type_text(&mut window, "hello world");
+    window.send_event(key(KeyCode::Left, KeyModifiers::CONTROL));
+    window.send_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Home, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Delete, KeyModifiers::NONE));
+    type_text(&mut window, "H");
+    assert!(window.screen().find("Name").is_none());
+    assert!(window.screen().find("Helloworld").is_some());
+    assert_eq!(window.get_state::<Changes>().unwrap().last, "Helloworld");
+
+    // Text wider than the input scrolls, and the max length is respected
+    window.send_event(key(KeyCode::End, KeyModifiers::NONE));
+    type_text(&mut window, "0123456789abc");
+    assert_eq!(window.get_state::<Changes>().unwrap().last, "Helloworld0123456789");
+    assert!(window.screen().find("Hello").is_none());
+    assert!(window.screen().find("456789").is_some());
+
+    // Passwords are masked
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    type_text(&mut window, "secret");
+    assert!(window.screen().find("secret").is_none());
+    assert!(window.screen().find("******").is_some());
+}
+
+#[test]
+/// Checks that a [`TextArea`] edits multiple lines, selects with Shift, undoes
+/// and redoes edits, typed runs in one step and only the latest ones, wraps
+/// long lines and shows line numbers.
+fn text_area() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Changes { last: String::new() })
+        .build();
+
+    let editor = TextArea::builder()
+        .text(String::from("port = 80"))
+        .line_numbers(true)
+        .on_change(|ctx, text| ctx.state::<Changes>().unwrap().last = text.to_string())
+        .build();
+    let wrapped = TextArea::builder()
+        .text(String::from("0123456789abcdefghijklmnopqrstuvwxyz"))
+        .wrap(true)
+        .build();
+    window.grid(Box::new(editor), 1, 1, 2, 2);
+    window.grid(Box::new(wrapped), 3, 1, 2, 1);
+    let last = |window: &mut Window| window.get_state::<Changes>().unwrap().last.clone();
+
+    // Typing a new line below the first one
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::End, KeyModifiers::NONE));
+    type_text(&mut window, "80");
+    window.send_event(key(KeyCode::Enter, KeyModifiers::NONE));
+    type_text(&mut window, "host = x");
+    assert_eq!(last(&mut window), "port = 8080\nhost = x");
+    let (x, y) = window.screen().find("1 port = 8080").unwrap();
+    assert_eq!(window.screen().find("2 host = x"), Some((x, y + 1)));
+
+    // Selecting the word before the cursor with Shift and replacing it
+    window.send_event(key(KeyCode::Left, KeyModifiers::SHIFT));
+    window.send_event(key(KeyCode::Left, KeyModifiers::SHIFT));
+    window.send_event(key(KeyCode::Left, KeyModifiers::SHIFT));
+    type_text(&mut window, ": y");
+    assert_eq!(last(&mut window), "port = 8080\nhost : y");
+
+    // Undoing the last two edits, each a run of typed chars, then redoing one
+    // of them
+    window.send_event(key(KeyCode::Char('z'), KeyModifiers::CONTROL));
+    assert_eq!(last(&mut window), "port = 8080\nhost = x");
+    window.send_event(key(KeyCode::Char('z'), KeyModifiers::CONTROL));
+    assert_eq!(last(&mut window), "port = 8080\n");
+    window.send_event(key(KeyCode::Char('y'), KeyModifiers::CONTROL));
+    assert_eq!(last(&mut window), "port = 8080\nhost = x");
+
+    // Backspace at the start of a line joins it with the one above
+    window.send_event(key(KeyCode::Home, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+    assert_eq!(last(&mut window), "port = 8080host = x");
+
+    // Only the latest 100 edits can be undone
+    for _ in 0..105 {
+        window.send_event(key(KeyCode::Enter, KeyModifiers::NONE));
+    }
+    for _ in 0..105 {
+        window.send_event(key(KeyCode::Char('z'), KeyModifiers::CONTROL));
+    }
+    assert_eq!(last(&mut window), "port = 8080\n\n\n\n\nhost = x");
+
+    // Long lines wrap instead of scrolling
+    let (x, y) = window.screen().find("0123456789ab").unwrap();
+    assert_eq!(window.screen().find("cdefghijklmn"), Some((x, y + 1)));
+    assert_eq!(window.screen().find("opqrstuvwxyz"), Some((x, y + 2)));
+}
+
+/// The application state used for testing selection callbacks
+struct Selections {
+    indices: Vec<usize>,
+}
+
+#[test]
+/// Checks that a [`ListView`] moves its selection with the keys and the mouse,
+/// scrolls with the wheel, and supports multi-select.
+fn list_view() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Selections { indices: Vec::new() })
+        .build();
+
+    let items: Vec<String> = (0..30).map(|i| format!("Item {}", i)).collect();
+    let tasks: Vec<String> = (0..30).map(|i| format!("Task {}", i)).collect();
+    let list = ListView::builder()
+        .items(items)
+        .on_select(|ctx, index| ctx.state::<Selections>().unwrap().indices.push(*index))
+        .build();
+    let multi = ListView::builder()
+        .items(tasks)
+        .multi_select(true)
+        .build();
+    window.grid(Box::new(list), 1, 1, 2, 1);
+    window.grid(Box::new(multi), 1, 2, 2, 1);
+    let theme = default_theme();
+    let (x, y) = window.screen().find("Item 0").unwrap();
+    assert_eq!(window.screen().cell(x, y).unwrap().bg, theme.get_selected_rgb());
+
+    // The keys move the selection, scrolling the list to keep it in view
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Down, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Down, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::End, KeyModifiers::NONE));
+    assert!(window.screen().find("Item 0").is_none());
+    assert_eq!(window.screen().cell(x, y).unwrap().bg, theme.get_bg_rgb());
+    let (_, last) = window.screen().find("Item 29").unwrap();
+    assert_eq!(window.screen().cell(x, last).unwrap().bg, theme.get_selected_rgb());
+    window.send_event(key(KeyCode::Home, KeyModifiers::NONE));
+    assert_eq!(window.get_state::<Selections>().unwrap().indices, vec![1, 2, 29, 0]);
+
+    // The wheel scrolls without changing the selection, and clicking selects
+    window.send_event(mouse(MouseEventKind::ScrollDown, x, y));
+    window.send_event(mouse(MouseEventKind::ScrollDown, x, y));
+    window.send_event(mouse(MouseEventKind::ScrollDown, x, y));
+    assert_eq!(window.screen().find("Item 3"), Some((x, y)));
+    window.send_event(click(x, y + 1));
+    assert_eq!(window.screen().cell(x, y + 1).unwrap().bg, theme.get_selected_rgb());
+    assert_eq!(window.get_state::<Selections>().unwrap().indices, vec![1, 2, 29, 0, 4]);
+
+    // With multi-select, Space and clicks toggle items
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Char(' '), KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Down, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Char(' '), KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Char(' '), KeyModifiers::NONE));
+    let (task_x, task_y) = window.screen().find("[ ] Task 2").unwrap();
+    window.send_event(click(task_x, task_y));
+    assert!(window.screen().find("[x] Task 0").is_some());
+    assert!(window.screen().find("[ ] Task 1").is_some());
+    assert!(window.screen().find("[x] Task 2").is_some());
+}
+
+#[test]
+/// Checks that clicking on the borders of a [`ListView`] doesn't select the
+/// items next to or hidden beyond them.
+fn list_view_border_click() {
+    let mut selections = Selections { indices: Vec::new() };
+    let mut list = ListView::builder()
+        .items((0..5).map(|i| i.to_string()).collect())
+        .on_select(|ctx, index| ctx.state::<Selections>().unwrap().indices.push(*index))
+        .build();
+    let mut buffer = Buffer::new(20, 10);
+
+    // The border leaves room for two items
+    list.draw(&mut buffer, 0, 0, 20, 4).unwrap();
+    let mut ctx = Context::new(Some(&mut selections));
+    list.event_send(click(1, 3), &mut ctx);
+    list.event_send(click(0, 2), &mut ctx);
+    list.event_send(click(19, 2), &mut ctx);
+    list.event_send(click(1, 2), &mut ctx);
+    assert_eq!(selections.indices, vec![1]);
+}
+
+#[test]
+/// Checks that a [`Table`] lays out its columns, truncates long cells, stripes
+/// and selects rows, and sorts when a header is clicked.
+fn table() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Selections { indices: Vec::new() })
+        .build();
+
+    let row = |name: &str, size: &str| vec![String::from(name), String::from(size)];
+    let table = Table::builder()
+        .column(String::from("Name"), ColumnWidth::Fill)
+        .column(String::from("Size"), ColumnWidth::Fixed(6))
+        .rows(vec![
+            row("b.txt", "100"),
+            row("a-very-long-file-name.txt", "9"),
+            row("c.txt", "20"),
+        ])
+        .zebra(true)
+        .on_select(|ctx, index| ctx.state::<Selections>().unwrap().indices.push(*index))
+        .build();
+    window.grid(Box::new(table), 1, 1, 2, 2);
+    let theme = default_theme();
+
+    // The header is followed by the rows, and long names are cut off
+    let (x, y) = window.screen().find("Name").unwrap();
+    let (size_x, _) = window.screen().find("Size").unwrap();
+    assert_eq!(window.screen().find("b.txt"), Some((x, y + 1)));
+    assert_eq!(window.screen().find("100"), Some((size_x, y + 1)));
+    let (_, cut) = window.screen().find("a-very-long").unwrap();
+    assert_eq!(window.screen().cell(size_x - 2, cut).unwrap().symbol, "…");
+
+    // The first row is selected, and the second is striped
+    assert_eq!(window.screen().cell(x, y + 1).unwrap().bg, theme.get_selected_rgb());
+    assert_eq!(window.screen().cell(x, y + 2).unwrap().bg, theme.get_stripe_rgb());
+
+    // Clicking on a row selects it
+    window.send_event(click(x, y + 3));
+    assert_eq!(window.screen().cell(x, y + 3).unwrap().bg, theme.get_selected_rgb());
+    assert_eq!(window.get_state::<Selections>().unwrap().indices, vec![2]);
+
+    // Clicking on the size header sorts the rows numerically, and clicking it
+    // again reverses them; the same row stays selected
+    window.send_event(click(size_x, y));
+    assert!(window.screen().find("Size ▲").is_some());
+    assert_eq!(window.screen().find("9"), Some((size_x, y + 1)));
+    assert_eq!(window.screen().find("c.txt"), Some((x, y + 2)));
+    assert_eq!(window.screen().cell(x, y + 2).unwrap().bg, theme.get_selected_rgb());
+    window.send_event(click(size_x, y));
+    assert!(window.screen().find("Size ▼").is_some());
+    assert_eq!(window.screen().find("100"), Some((size_x, y + 1)));
+
+    // The keys move the selection
+    window.send_event(key(KeyCode::Home, KeyModifiers::NONE));
+    assert_eq!(window.get_state::<Selections>().unwrap().indices, vec![2, 0]);
+}
+
+#[test]
+/// Checks that clicking on the bottom border of a [`Table`] doesn't select the
+/// row hidden below it.
+fn table_border_click() {
+    let mut selections = Selections { indices: Vec::new() };
+    let mut table = Table::builder()
+        .column(String::from("Name"), ColumnWidth::Fill)
+        .rows((0..5).map(|i| vec![i.to_string()]).collect())
+        .on_select(|ctx, index| ctx.state::<Selections>().unwrap().indices.push(*index))
+        .build();
+    let mut buffer = Buffer::new(20, 10);
+
+    // The border and the header leave room for two rows
+    table.draw(&mut buffer, 0, 0, 20, 5).unwrap();
+    let mut ctx = Context::new(Some(&mut selections));
+    table.event_send(click(1, 4), &mut ctx);
+    table.event_send(click(1, 3), &mut ctx);
+    assert_eq!(selections.indices, vec![1]);
+}
+
+/// The application state used for testing tree callbacks
+struct TreeEvents {
+    loaded: Vec<String>,
+    selected: Vec<String>,
+}
+
+/// Returns a [`TreeNode`] with the text `text` and the children `children`
+fn node(text: &str, children: Vec<TreeNode>) -> TreeNode {
+    children
+        .into_iter()
+        .fold(TreeNode::builder().text(String::from(text)), |node, child| node.child(child))
+        .build()
+}
+
+#[test]
+/// Checks that a [`Tree`] draws guide lines, expands and collapses nodes with
+/// the keys and the mouse, loads lazy children, and reports selections.
+fn tree() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(TreeEvents { loaded: Vec::new(), selected: Vec::new() })
+        .build();
+
+    let tree = Tree::builder()
+        .node(node("src", vec![
+            node("widgets", vec![node("button.rs", vec![])]),
+            node("lib.rs", vec![]),
+        ]))
+        .node(TreeNode::builder().text(String::from("target")).lazy(true).build())
+        .on_load(|ctx, path| {
+            ctx.state::<TreeEvents>().unwrap().loaded = path.to_vec();
+            vec![node("debug", vec![])]
+        })
+        .on_select(|ctx, path| ctx.state::<TreeEvents>().unwrap().selected = path.to_vec())
+        .build();
+    window.grid(Box::new(tree), 1, 1, 3, 2);
+    let (x, y) = window.screen().find("▸ src").unwrap();
+    assert_eq!(window.screen().find("▸ target"), Some((x, y + 1)));
+
+    // Right expands the selected node, then moves to its first child
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Right, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Right, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Right, KeyModifiers::NONE));
+    assert_eq!(window.screen().find("▾ src"), Some((x, y)));
+    assert_eq!(window.screen().find("├─▾ widgets"), Some((x, y + 1)));
+    assert_eq!(window.screen().find("│ ╰─  button.rs"), Some((x, y + 2)));
+    assert_eq!(window.screen().find("╰─  lib.rs"), Some((x, y + 3)));
+    let selected = |window: &mut Window| {
+        window.get_state::<TreeEvents>().unwrap().selected.clone()
+    };
+    assert_eq!(selected(&mut window), vec!["src", "widgets"]);
+
+    // Left collapses the selected node, then moves to its parent
+    window.send_event(key(KeyCode::Left, KeyModifiers::NONE));
+    assert!(window.screen().find("button.rs").is_none());
+    window.send_event(key(KeyCode::Left, KeyModifiers::NONE));
+    assert_eq!(selected(&mut window), vec!["src"]);
+
+    // Clicking on the arrow of a lazy node loads its children and expands it
+    let (target_x, target_y) = window.screen().find("▸ target").unwrap();
+    window.send_event(click(target_x, target_y));
+    assert_eq!(window.get_state::<TreeEvents>().unwrap().loaded, vec!["target"]);
+    assert_eq!(window.screen().find("╰─  debug"), Some((x, target_y + 1)));
+    assert_eq!(selected(&mut window), vec!["target"]);
+}
+
+#[test]
+/// Checks that clicking on the borders of a [`Tree`] doesn't select the nodes
+/// next to or hidden beyond them.
+fn tree_border_click() {
+    let mut events = TreeEvents { loaded: Vec::new(), selected: Vec::new() };
+    let mut tree = (0..5)
+        .fold(Tree::builder(), |tree, i| tree.node(node(&i.to_string(), vec![])))
+        .on_select(|ctx, path| ctx.state::<TreeEvents>().unwrap().selected = path.to_vec())
+        .build();
+    let mut buffer = Buffer::new(20, 10);
+
+    // The border leaves room for two nodes
+    tree.draw(&mut buffer, 0, 0, 20, 4).unwrap();
+    let mut ctx = Context::new(Some(&mut events));
+    tree.event_send(click(5, 3), &mut ctx);
+    tree.event_send(click(0, 2), &mut ctx);
+    tree.event_send(click(19, 2), &mut ctx);
+    assert!(ctx.state::<TreeEvents>().unwrap().selected.is_empty());
+    tree.event_send(click(5, 2), &mut ctx);
+    assert_eq!(events.selected, vec![String::from("1")]);
+}
+
+#[test]
+/// Checks that [`Tabs`] only draw the active page, and switch pages with clicks,
+/// Ctrl+PageUp/PageDown and the number keys, taking the focus from hidden
+/// children.
+fn tabs() {
+    let mut window = Window::headless(80, 24);
+
+    let first = Label::builder()
+        .text(String::from("First page"))
+        .focusable(true)
+        .build();
+    let second = TextInput::builder()
+        .value(String::from("Second page"))
+        .build();
+    let mut tabs = Tabs::builder()
+        .page(String::from("One"), Grid::builder().width(1).height(1).build())
+        .page(String::from("Two"), Grid::builder().width(1).height(1).build())
+        .build();
+    tabs.grid(Box::new(first), 1, 1, 1, 1);
+    let mut tabs = tabs.set_active(1);
+    tabs.grid(Box::new(second), 1, 1, 1, 1);
+    window.grid(Box::new(tabs.set_active(0)), 1, 1, 3, 3);
+    let theme = default_theme();
+
+    // Only the first page is drawn, below the tab bar
+    let (x, y) = window.screen().find(" One │ Two │").unwrap();
+    assert_eq!(window.screen().cell(x, y).unwrap().bg, theme.get_selected_rgb());
+    assert!(window.screen().find("First page").is_some());
+    assert!(window.screen().find("Second page").is_none());
+
+    // Clicking on a tab switches to its page
+    window.send_event(click(x + 7, y));
+    assert!(window.screen().find("First page").is_none());
+    assert!(window.screen().find("Second page").is_some());
+    assert_eq!(window.screen().cell(x + 6, y).unwrap().bg, theme.get_selected_rgb());
+
+    // The focused text input doesn't pass on Ctrl+PageUp, so it bubbles up to the
+    // tabs; the hidden text input loses the focus, and typing goes nowhere
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::PageUp, KeyModifiers::CONTROL));
+    assert!(window.screen().find("First page").is_some());
+    window.send_event(key(KeyCode::Char('x'), KeyModifiers::NONE));
+    window.send_event(key(KeyCode::PageDown, KeyModifiers::CONTROL));
+    assert!(window.screen().find("Second page").is_some());
+    assert!(window.screen().find("Second pagex").is_none());
+
+    // The number keys switch pages too
+    window.send_event(key(KeyCode::Char('1'), KeyModifiers::NONE));
+    assert!(window.screen().find("First page").is_some());
+}
+
+#[test]
+/// Checks that a [`ProgressBar`] fills in eighths of a cell, shows its label,
+/// and bounces in indeterminate mode, and that a [`Gauge`] fills from the bottom.
+fn progress() {
+    let theme = default_theme();
+    let mut buffer = Buffer::new(10, 10);
+
+    // 10 cells at 15% is 1.5 cells, so one full cell and a half block
+    let mut progress = ProgressBar::builder()
+        .border(false)
+        .value(0.15)
+        .percentage(true)
+        .build();
+    progress.draw(&mut buffer, 0, 0, 10, 1).unwrap();
+    assert_eq!(buffer.line(0), " ▌ 15%    ");
+    assert_eq!(buffer.cell(0, 0).unwrap().bg, theme.get_fg_rgb());
+    assert_eq!(buffer.cell(1, 0).unwrap().bg, theme.get_bg_rgb());
+    progress.set_value(2.0);
+    progress.draw(&mut buffer, 0, 0, 10, 1).unwrap();
+    assert_eq!(buffer.line(0), "   100%   ");
+    assert_eq!(buffer.cell(9, 0).unwrap().bg, theme.get_fg_rgb());
+
+    // The indeterminate block is a quarter of the bar, and bounces off the ends
+    let mut bouncing = ProgressBar::builder()
+        .border(false)
+        .indeterminate(true)
+        .build();
+    let filled = |buffer: &Buffer| {
+        (0..10)
+            .filter(|x| buffer.cell(*x, 1).unwrap().bg == theme.get_fg_rgb())
+            .collect::<Vec<u16>>()
+    };
+    bouncing.draw(&mut buffer, 0, 1, 10, 1).unwrap();
+    assert_eq!(filled(&buffer), vec![0, 1]);
+    for _ in 0..9 { bouncing.pulse(); }
+    bouncing.draw(&mut buffer, 0, 1, 10, 1).unwrap();
+    assert_eq!(filled(&buffer), vec![7, 8]);
+
+    // A gauge 4 rows tall at 40% is 1.6 rows, so one full row and five eighths
+    let mut gauge = Gauge::builder()
+        .border(false)
+        .value(0.4)
+        .build();
+    gauge.draw(&mut buffer, 0, 2, 2, 4).unwrap();
+    assert_eq!(buffer.cell(0, 4).unwrap().symbol, "▅");
+    assert_eq!(buffer.cell(1, 5).unwrap().bg, theme.get_fg_rgb());
+    assert_eq!(buffer.cell(1, 3).unwrap().bg, theme.get_bg_rgb());
+}
+
+/// The application state used for testing timers
+struct Ticks {
+    intervals: u32,
+    timeouts: u32,
+}
+
+#[test]
+/// Checks that [`Window::update()`] runs due timers, skips cancelled ones, and
+/// gives the widgets a tick at the tick rate.
+fn timers() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Ticks { intervals: 0, timeouts: 0 })
+        .tick_rate(Duration::from_millis(100))
+        .build();
+    let bar = ProgressBar::builder()
+        .indeterminate(true)
+        .build();
+    window.grid(Box::new(bar), 1, 1, 1, 1);
+    window.add_interval(Duration::from_secs(1), |ctx| {
+        ctx.state::<Ticks>().unwrap().intervals += 1;
+    });
+    window.add_timer(Duration::from_millis(500), |ctx| {
+        ctx.state::<Ticks>().unwrap().timeouts += 1;
+    });
+    let cancelled = window.add_timer(Duration::from_millis(200), |ctx| ctx.quit());
+    assert!(window.cancel_timer(cancelled));
+    assert!(!window.cancel_timer(cancelled));
+    let start = Instant::now();
+
+    // Every tick moves the indeterminate bar
+    let before = window.screen().clone();
+    assert!(window.update(start + Duration::from_millis(150)));
+    assert_ne!(*window.screen(), before);
+
+    // The one-shot timer runs once, and the interval skips the periods it missed
+    assert!(window.update(start + Duration::from_millis(1100)));
+    assert!(window.update(start + Duration::from_millis(3500)));
+    assert!(window.update(start + Duration::from_millis(3600)));
+    let ticks = window.get_state::<Ticks>().unwrap();
+    assert_eq!((ticks.intervals, ticks.timeouts), (2, 1));
+
+    // A timer can make the window quit
+    window.add_timer(Duration::ZERO, |ctx| ctx.quit());
+    assert!(!window.update(Instant::now()));
+}
+
+/// The application state used for testing messages
+struct Inbox {
+    lines: Vec<String>,
+    total: u32,
+}
+
+#[test]
+/// Checks that messages and closures posted through a [`Sender`] from other
+/// threads are picked up by [`Window::update()`] and handed to the handler for
+/// their type.
+fn messages() {
+    let mut window = Window::builder()
+        .backend(MemoryBackend::new(80, 24))
+        .state(Inbox { lines: Vec::new(), total: 0 })
+        .on_message(|window: &mut Window, count: u32| {
+            window.get_state::<Inbox>().unwrap().total += count;
+        })
+        .on_message(|window: &mut Window, line: String| {
+            window.get_state::<Inbox>().unwrap().lines.push(line);
+        })
+        .build();
 
-    // Run the app (uncomment if you want to have to hit Ctrl+C to continue testing)
-    window.run();
+    // Post from a few threads; nothing is handled until the window updates
+    let workers: Vec<_> = (1..=3).map(|i| {
+        let sender = window.sender();
+        std::thread::spawn(move || {
+            assert!(sender.send(i as u32));
+            assert!(sender.send(format!("Worker {}", i)));
+        })
+    }).collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert_eq!(window.get_state::<Inbox>().unwrap().total, 0);
+    assert!(window.update(Instant::now()));
+    let inbox = window.get_state::<Inbox>().unwrap();
+    assert_eq!(inbox.total, 6);
+    inbox.lines.sort();
+    assert_eq!(inbox.lines, vec!["Worker 1", "Worker 2", "Worker 3"]);
+
+    // Messages nobody handles are dropped, and closures run with the window
+    let sender = window.sender();
+    assert!(sender.send(1.5f64));
+    assert!(sender.call(|window| window.get_state::<Inbox>().unwrap().total = 0));
+    assert!(window.update(Instant::now()));
+    assert_eq!(window.get_state::<Inbox>().unwrap().total, 0);
+    assert!(sender.call(|window| window.quit()));
+    assert!(!window.update(Instant::now()));
+
+    // Once the window is gone, nothing can be posted anymore
+    drop(window);
+    assert!(!sender.send(1u32));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+/// Checks that [`Window::run_stream()`] handles the events from a stream, runs
+/// the timers while waiting for more, and returns once the window quits.
+async fn run_stream() {
+    use futures_util::stream::{self, StreamExt};
+
+    let mut window = Window::headless(80, 24);
+    let input = TextInput::new();
+    window.grid(Box::new(input), 1, 1, 2, 1);
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    window.add_timer(Duration::from_millis(50), |ctx| ctx.quit());
+
+    // The stream never ends, so only the timer can stop the window
+    let events = stream::iter(vec![
+        Ok(key(KeyCode::Char('h'), KeyModifiers::NONE)),
+        Ok(key(KeyCode::Char('i'), KeyModifiers::NONE)),
+    ]).chain(stream::pending());
+    window.run_stream(events).await.unwrap();
+    assert!(window.screen().find("hi").is_some());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+/// Checks that [`Window::run_stream()`] waits for events alone when the window
+/// has no timers to wake it up.
+async fn run_stream_without_timers() {
+    use futures_util::stream::{self, StreamExt};
+
+    let mut window = Window::headless(80, 24);
+    window.add(Box::new(Label::builder().text(String::from("Idle")).build()), 1, 1);
+    let events = stream::iter(vec![Ok(key(KeyCode::Char('c'), KeyModifiers::CONTROL))])
+        .chain(stream::pending());
+    window.run_stream(events).await.unwrap();
+    assert!(window.screen().find("Idle").is_some());
+}
+
+/// A writer that keeps everything written to it where a test can still read it
+/// after handing the writer to a window
+#[cfg(feature = "tokio")]
+#[derive(Clone, Default)]
+struct SharedWriter(Rc<RefCell<Vec<u8>>>);
+#[cfg(feature = "tokio")]
+impl Write for SharedWriter {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(bytes)
+    }
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+/// Checks that [`Window::run_stream()`] sets up the screen before the first
+/// event, even for a window without any children.
+async fn run_stream_enters_backend() {
+    use futures_util::stream;
+    use rusty_panther::backend::CrosstermBackend;
+
+    let writer = SharedWriter::default();
+    let mut window = Window::builder()
+        .backend(CrosstermBackend::new(writer.clone()).fixed_size(80, 24))
+        .build();
+    let events = stream::iter(vec![Ok(key(KeyCode::Char('c'), KeyModifiers::CONTROL))]);
+    window.run_stream(events).await.unwrap();
+    let output = String::from_utf8_lossy(&writer.0.borrow()).to_string();
+    assert!(output.starts_with("\x1b[?1049h"));
+}
+
+#[test]
+/// Checks that a child gridded outside its parent's grid makes drawing fail,
+/// instead of panicking, and that the window keeps the error for its caller.
+fn layout_errors() {
+    let mut window = Window::headless(80, 24);
+    let label = Label::builder()
+        .text(String::from("Lost"))
+        .build();
+    let mut frame = Frame::builder()
+        .set_grid(Grid::builder().width(2).height(2).build())
+        .build();
+    frame.grid(Box::new(label), 1, 3, 1, 1);
+    window.grid(Box::new(frame), 1, 1, 2, 2);
+    assert!(matches!(window.take_error(), Some(Error::NoSuchColumn(3))));
+    assert!(matches!(window.draw_children(), Err(Error::NoSuchColumn(3))));
+    assert!(window.screen().find("Lost").is_none());
+
+    // Row 257 doesn't wrap around to row 1
+    let mut window = Window::headless(80, 24);
+    let label = Label::builder()
+        .text(String::from("Wrapped"))
+        .build();
+    let mut frame = Frame::builder()
+        .set_grid(Grid::builder().width(2).height(2).build())
+        .build();
+    frame.grid(Box::new(label), 257, 1, 1, 1);
+    window.grid(Box::new(frame), 1, 1, 2, 2);
+    assert!(matches!(window.take_error(), Some(Error::NoSuchRow(257))));
+    assert!(window.screen().find("Wrapped").is_none());
+}
+
+#[test]
+/// Checks that frames and tabs in a window too small for their grids are drawn
+/// without the children that don't fit, instead of panicking, and that those
+/// children stay hidden when they change.
+fn small_layouts() {
+    let mut window = Window::headless(20, 10);
+    let mut frame = Frame::new();
+    let tiny = Label::builder().text(String::from("Tiny")).build();
+    let tiny = frame.grid(Box::new(tiny), 1, 1, 1, 1);
+    window.grid(Box::new(frame), 1, 1, 1, 1);
+    let mut tabs = Tabs::builder()
+        .page(String::from("A"), Grid::new())
+        .build();
+    tabs.grid(Box::new(TextInput::new()), 1, 1, 1, 1);
+    window.grid(Box::new(tabs), 2, 1, 1, 1);
+    window.draw_children().unwrap();
+    assert!(window.screen().find("A │").is_some());
+    assert!(window.screen().find("Tiny").is_none());
+
+    // A label that wasn't drawn isn't drawn by itself when it changes, either
+    window.get_mut::<Label>(tiny).unwrap().update_text(String::from("Huge"));
+    window.update(Instant::now());
+    assert!(window.screen().find("Huge").is_none());
+    let mut loose = Label::new();
+    loose.update_text(String::from("Loose"));
+    assert!(!loose.draw_dirty(&mut Buffer::new(20, 10)).unwrap());
+}
+
+#[test]
+/// Checks that widgets can be reached by their IDs after being gridded, and that
+/// they can be removed and moved to another parent, keeping their IDs and the
+/// focus.
+fn widget_tree() {
+    let mut window = Window::headless(80, 24);
+
+    let bar = window.grid(Box::new(ProgressBar::new()), 5, 1, 1, 2);
+    let mut frame = Frame::builder()
+        .title(String::from("Panel"))
+        .set_grid(Grid::builder().width(1).height(1).build())
+        .build();
+    let gone = Label::builder().text(String::from("Gone")).build();
+    let label = frame.grid(Box::new(gone), 1, 1, 1, 1);
+    let frame = window.grid(Box::new(frame), 1, 1, 2, 2);
+    let input = window.add(Box::new(TextInput::new()), 50, 2);
+
+    // Widgets can be read and changed through their IDs, but only as their own type
+    assert!(window.get::<Label>(label).is_some());
+    assert!(window.get::<Label>(bar).is_none());
+    assert!(window.get::<Frame>(frame).is_some());
+    window.get_mut::<ProgressBar>(bar).unwrap().set_value(0.5);
+    assert_eq!(window.get::<ProgressBar>(bar).unwrap().get_value(), 0.5);
+
+    // Removing a widget from inside the frame takes it off the screen
+    assert!(window.screen().find("Gone").is_some());
+    let removed = window.remove(label).unwrap();
+    assert!(window.screen().find("Gone").is_none());
+    assert!(window.get::<Label>(label).is_none());
+    assert!(window.remove(label).is_none());
+    drop(removed);
+
+    // A focused widget keeps its ID and the focus when it moves into the frame
+    window.send_event(key(KeyCode::Tab, KeyModifiers::NONE));
+    type_text(&mut window, "ab");
+    assert!(window.reparent(input, Some(frame), 1, 1, 1, 1));
+    type_text(&mut window, "c");
+    assert_eq!(window.get::<TextInput>(input).unwrap().get_value(), "abc");
+    let (_, panel_y) = window.screen().find("Panel").unwrap();
+    let (_, input_y) = window.screen().find("abc").unwrap();
+    assert!(input_y > panel_y && input_y < 10);
+
+    // A widget can't be moved into itself, or into a widget that isn't a parent
+    assert!(!window.reparent(frame, Some(frame), 1, 1, 1, 1));
+    assert!(!window.reparent(frame, Some(input), 1, 1, 1, 1));
+    assert!(!window.reparent(frame, Some(bar), 1, 1, 1, 1));
+    assert!(window.reparent(input, None, 4, 4, 1, 1));
+    assert_eq!(window.get::<TextInput>(input).unwrap().get_value(), "abc");
+
+    // Tabs without pages can't hold the widget, so it stays where it was, and
+    // adding one to them directly is an error rather than losing it
+    let tabs = window.grid(Box::new(Tabs::new()), 5, 5, 1, 1);
+    assert!(!window.reparent(input, Some(tabs), 1, 1, 1, 1));
+    assert_eq!(window.get::<TextInput>(input).unwrap().get_value(), "abc");
+    window.get_mut::<Tabs>(tabs).unwrap().grid(Box::new(Label::new()), 1, 1, 1, 1);
+    assert!(matches!(window.draw_children(), Err(Error::NoPage)));
+}
+
+#[test]
+/// Checks that a [`Label`] can be changed after it is gridded, and that only the
+/// label is drawn again when it is, unless its size changed.
+fn label_setters() {
+    let mut window = Window::headless(80, 24);
+
+    let status = window.grid(Box::new(Label::new()), 1, 1, 1, 2);
+    let bar = window.grid(Box::new(ProgressBar::new()), 2, 1, 1, 2);
+    let counter = Label::builder().text(String::from("0")).build();
+    let counter = window.add(Box::new(counter), 60, 1);
+    let (bar_x, bar_y, _, _) = window.get::<ProgressBar>(bar).unwrap().get_area();
+    let (bar_x, bar_y) = (bar_x + 1, bar_y + 1);
+    assert_eq!(window.screen().cell(bar_x, bar_y).unwrap().bg, default_theme().get_bg_rgb());
+
+    // The progress bar doesn't keep track of its changes, so it stays as it was
+    // while just the label is drawn again
+    window.get_mut::<ProgressBar>(bar).unwrap().set_value(1.0);
+    window.get_mut::<Label>(status).unwrap().update_text(String::from("Loading"));
+    assert!(window.screen().find("Loading").is_none());
+    assert!(window.update(Instant::now()));
+    assert!(window.screen().find("Loading").is_some());
+    assert_eq!(window.screen().cell(bar_x, bar_y).unwrap().bg, default_theme().get_bg_rgb());
+
+    // A new theme changes the label's colors
+    let mut theme = default_theme();
+    theme.fg = (255, 0, 0);
+    let red = theme.get_fg_rgb();
+    window.get_mut::<Label>(status).unwrap().update_theme(theme);
+    assert!(window.update(Instant::now()));
+    let (x, y) = window.screen().find("Loading").unwrap();
+    assert_eq!(window.screen().cell(x, y).unwrap().fg, red);
+    assert_eq!(window.get::<Label>(status).unwrap().get_text(), "Loading");
+
+    // A label placed with `add()` grows with its text and width, which draws
+    // the whole window again
+    window.get_mut::<Label>(counter).unwrap().update_text(String::from("100"));
+    window.get_mut::<Label>(counter).unwrap().update_width(Some(10));
+    assert!(window.update(Instant::now()));
+    assert!(window.screen().find("100").is_some());
+    assert_eq!(window.screen().cell(70, 1).unwrap().symbol, "╮");
+    assert_eq!(window.screen().cell(bar_x, bar_y).unwrap().bg, default_theme().get_fg_rgb());
+}
+
+#[test]
+/// Checks that a [`Label`] wraps and aligns its text inside its area, and cuts
+/// off whatever still doesn't fit.
+fn label_layout() {
+    let mut buffer = Buffer::new(20, 10);
+
+    // Words wrap inside the border, and each line is centered
+    let mut centered = Label::builder()
+        .text(String::from("the quick brown fox"))
+        .wrap(Wrap::Word)
+        .align(Align::Center)
+        .vertical_align(VerticalAlign::Bottom)
+        .build();
+    centered.draw(&mut buffer, 0, 0, 11, 5).unwrap();
+    assert_eq!(buffer.line(1), "│          │        ");
+    assert_eq!(buffer.line(2), "│the quick │        ");
+    assert_eq!(buffer.line(3), "│brown fox │        ");
+    assert_eq!(buffer.line(4), "╰──────────╯        ");
+
+    // Newlines start new lines, and the lines that don't fit get an ellipsis
+    let mut buffer = Buffer::new(20, 10);
+    let mut cut = Label::builder()
+        .text(String::from("first line\nsecond\nthird"))
+        .border((false, false))
+        .align(Align::Right)
+        .overflow(Overflow::Ellipsis)
+        .build();
+    cut.draw(&mut buffer, 0, 0, 8, 2).unwrap();
+    assert_eq!(buffer.line(0), "first l…            ");
+    assert_eq!(buffer.line(1), " second…            ");
+
+    // A label placed with `add()` grows to fit all of its lines
+    let mut window = Window::headless(80, 24);
+    let text = String::from("one\ntwo\nthree");
+    window.add(Box::new(Label::builder().text(text).build()), 10, 10);
+    assert_eq!(window.screen().find("three"), Some((11, 13)));
+    assert_eq!(window.screen().cell(10, 14).unwrap().symbol, "╰");
+}
+
+#[test]
+/// Checks that widgets measure text by the cells it takes up on the screen, so
+/// that wide and combined graphemes are laid out, cut off and edited whole.
+fn unicode_text() {
+
+    // Wide graphemes take up two cells each, and are never split between lines
+    let mut buffer = Buffer::new(10, 4);
+    let mut wrapped = Label::builder()
+        .text(String::from("日本語のテキスト"))
+        .border((false, false))
+        .wrap(Wrap::Char)
+        .build();
+    wrapped.draw(&mut buffer, 0, 0, 7, 3).unwrap();
+    assert_eq!(buffer.line(0), "日本語    ");
+    assert_eq!(buffer.line(1), "のテキ    ");
+    assert_eq!(buffer.line(2), "スト      ");
+    assert_eq!(buffer.cell(1, 0).unwrap().symbol, "");
+
+    // A combining accent doesn't add to the width of a label
+    let mut window = Window::headless(40, 10);
+    window.add(Box::new(Label::builder().text(String::from("cafe\u{301}")).build()), 0, 0);
+    assert_eq!(window.screen().find("cafe\u{301}"), Some((1, 1)));
+    assert_eq!(window.screen().cell(5, 1).unwrap().symbol, "│");
+
+    // Table cells are cut off by width, with an ellipsis
+    let mut table = Table::builder()
+        .column(String::from("Name"), ColumnWidth::Fixed(5))
+        .rows(vec![vec![String::from("日本語テキスト")]])
+        .build();
+    let mut buffer = Buffer::new(10, 4);
+    table.draw(&mut buffer, 0, 0, 7, 4).unwrap();
+    assert!(buffer.find("日本…").is_some());
 
-    // This makes sure we don't mess up anything in the terminal while testing
-    // window.quit();
+    // Editing moves over and deletes whole graphemes, and the cursor covers the
+    // cells of the grapheme under it
+    let input = window.add(Box::new(TextInput::builder().build()), 0, 5);
+    window.send_event(click(1, 6));
+    type_text(&mut window, "日本e\u{301}x");
+    window.send_event(key(KeyCode::Left, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Left, KeyModifiers::NONE));
+    window.send_event(key(KeyCode::Backspace, KeyModifiers::NONE));
+    assert_eq!(window.get::<TextInput>(input).unwrap().get_value(), "日e\u{301}x");
+    assert!(window.screen().find("日e\u{301}x").is_some());
+    let cursor = window.screen().cell(3, 6).unwrap().attributes;
+    assert!(cursor.has(Attribute::Reverse));
+    window.send_event(key(KeyCode::Left, KeyModifiers::NONE));
+    let cursor = window.screen().cell(2, 6).unwrap().attributes;
+    assert!(cursor.has(Attribute::Reverse));
+    window.send_event(key(KeyCode::Delete, KeyModifiers::NONE));
+    assert_eq!(window.get::<TextInput>(input).unwrap().get_value(), "e\u{301}x");
 }